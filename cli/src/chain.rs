@@ -0,0 +1,94 @@
+//! The ledger the CLI talks to: a JSON-RPC node in practice, anything that
+//! can read accounts and land transactions in tests.
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+/// The parts of an on-chain account the CLI needs.
+#[derive(Clone, Debug)]
+pub struct AccountData {
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+pub trait Chain {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<AccountData>>;
+
+    /// Program accounts whose data starts with `discriminator`.
+    fn get_program_accounts(&self, discriminator: &[u8]) -> Result<Vec<(Pubkey, AccountData)>>;
+
+    /// Sign with `signers` (the first pays) and land the transaction,
+    /// returning its signature.
+    fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<String>;
+}
+
+/// [`Chain`] backed by a Solana JSON-RPC node.
+pub struct RpcChain {
+    client: RpcClient,
+}
+
+impl RpcChain {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+        }
+    }
+}
+
+impl Chain for RpcChain {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<AccountData>> {
+        let account = self
+            .client
+            .get_account_with_commitment(address, self.client.commitment())?
+            .value;
+        Ok(account.map(|account| AccountData {
+            owner: account.owner,
+            lamports: account.lamports,
+            data: account.data,
+        }))
+    }
+
+    fn get_program_accounts(&self, discriminator: &[u8]) -> Result<Vec<(Pubkey, AccountData)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                discriminator.to_vec(),
+            ))]),
+            account_config: RpcAccountInfoConfig::default(),
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = self
+            .client
+            .get_program_accounts_with_config(&arcadium_client::ID, config)?;
+        Ok(accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let data = AccountData {
+                    owner: account.owner,
+                    lamports: account.lamports,
+                    data: account.data,
+                };
+                (address, data)
+            })
+            .collect())
+    }
+
+    fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<String> {
+        let blockhash = self.client.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&signers[0].pubkey()),
+            signers,
+            blockhash,
+        );
+        Ok(self.client.send_and_confirm_transaction(&tx)?.to_string())
+    }
+}
@@ -0,0 +1,446 @@
+//! The `arcadium` command-line tool for operators and agent owners.
+//!
+//! Commands are parsed with clap into [`Command`] and executed by [`run`]
+//! against any [`Chain`], so the same code path serves a live RPC node and
+//! an in-process test validator.
+
+pub mod chain;
+
+use std::io::Write;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use anyhow::{anyhow, bail, Context, Result};
+use arcadium_client::{
+    instructions as ix, job_status_name, pda, Agent, AgentMetadata, AgentMetadataInput, Category, Job, PaymentMint,
+    Platform,
+};
+use clap::{Args, Parser, Subcommand};
+use solana_sdk::signature::{Keypair, Signer};
+
+pub use chain::{AccountData, Chain, RpcChain};
+
+#[derive(Parser, Debug)]
+#[command(name = "arcadium", version, about = "Manage the Arcadium agent marketplace")]
+pub struct Cli {
+    /// JSON-RPC endpoint
+    #[arg(long, short = 'u', global = true, env = "ARCADIUM_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    pub url: String,
+
+    /// Keypair file that signs and pays for transactions
+    #[arg(long, short = 'k', global = true, env = "ARCADIUM_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    pub keypair: String,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Create the platform account, with the signer as authority
+    InitPlatform {
+        #[arg(long, default_value_t = 250)]
+        fee_bps: u16,
+        #[arg(long, default_value_t = 259_200)]
+        review_window_secs: i64,
+    },
+    /// Create an agent category (signer must be the platform authority)
+    CreateCategory {
+        #[arg(long)]
+        id: u16,
+        #[arg(long)]
+        name: String,
+    },
+    /// Rename or (de)activate a category (signer must be the platform authority)
+    UpdateCategory {
+        #[arg(long)]
+        id: u16,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        active: Option<bool>,
+    },
+    /// List categories with their agent counts
+    ListCategories,
+    /// Register a new agent owned by the signer
+    RegisterAgent {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        skill_uri: String,
+        /// In lamports, or base units of `--mint`
+        #[arg(long)]
+        price: u64,
+        /// Price the agent in this SPL mint instead of SOL
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        #[command(flatten)]
+        metadata: MetadataArgs,
+    },
+    /// Change an agent's listing; omitted fields are left as they are
+    UpdateAgent {
+        #[arg(long)]
+        agent: Pubkey,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        skill_uri: Option<String>,
+        #[arg(long)]
+        price: Option<u64>,
+        #[arg(long)]
+        active: Option<bool>,
+        #[command(flatten)]
+        metadata: MetadataArgs,
+    },
+    /// List jobs, optionally only those of one agent or client
+    ListJobs {
+        #[arg(long)]
+        agent: Option<Pubkey>,
+        #[arg(long)]
+        client: Option<Pubkey>,
+    },
+    /// Accept a delivered job and release payment (signer must be the client)
+    CompleteJob {
+        #[arg(long)]
+        job: Pubkey,
+    },
+    /// Rate a completed job (signer must be the client)
+    Rate {
+        #[arg(long)]
+        job: Pubkey,
+        /// 1 to 5
+        #[arg(long)]
+        rating: u8,
+        #[arg(long, default_value = "")]
+        comment: String,
+    },
+    /// Withdraw earnings from an agent's vault (signer must be the owner)
+    Withdraw {
+        #[arg(long)]
+        agent: Pubkey,
+        /// In lamports, or base units of the agent's mint
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Print an account
+    #[command(subcommand)]
+    Show(Show),
+}
+
+/// Agent metadata flags. On `update-agent`, only the given fields change;
+/// any `--tag` replaces the whole tag list, and any `--category` the whole
+/// category list. `--clear-tags` / `--clear-categories` empty them.
+#[derive(Args, Debug, Default)]
+pub struct MetadataArgs {
+    /// Webhook URL new jobs are dispatched to
+    #[arg(long)]
+    pub endpoint: Option<String>,
+    #[arg(long)]
+    pub description: Option<String>,
+    /// Category tag; repeat for several
+    #[arg(long = "tag")]
+    pub tags: Vec<String>,
+    /// Category ID; repeat for several
+    #[arg(long = "category")]
+    pub categories: Vec<u16>,
+    /// Remove every tag
+    #[arg(long, conflicts_with = "tags")]
+    pub clear_tags: bool,
+    /// Leave every category
+    #[arg(long, conflicts_with = "categories")]
+    pub clear_categories: bool,
+    /// Hex-encoded X25519 public key for encrypted task payloads
+    #[arg(long, value_parser = parse_key)]
+    pub encryption_key: Option<[u8; 32]>,
+}
+
+impl MetadataArgs {
+    fn is_empty(&self) -> bool {
+        self.endpoint.is_none()
+            && self.description.is_none()
+            && self.tags.is_empty()
+            && self.categories.is_empty()
+            && !self.clear_tags
+            && !self.clear_categories
+            && self.encryption_key.is_none()
+    }
+
+    /// `current` with the given fields replaced.
+    fn apply(self, current: AgentMetadataInput) -> AgentMetadataInput {
+        AgentMetadataInput {
+            endpoint_url: self.endpoint.unwrap_or(current.endpoint_url),
+            description: self.description.unwrap_or(current.description),
+            tags: match (self.clear_tags, self.tags.is_empty()) {
+                (true, _) => Vec::new(),
+                (false, true) => current.tags,
+                (false, false) => self.tags,
+            },
+            categories: match (self.clear_categories, self.categories.is_empty()) {
+                (true, _) => Vec::new(),
+                (false, true) => current.categories,
+                (false, false) => self.categories,
+            },
+            encryption_key: self.encryption_key.or(current.encryption_key),
+        }
+    }
+}
+
+fn parse_key(s: &str) -> std::result::Result<[u8; 32], String> {
+    let bytes = hex::decode(s).map_err(|e| e.to_string())?;
+    bytes.try_into().map_err(|_| "expected 32 bytes".to_string())
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Show {
+    Agent { address: Pubkey },
+    Job { address: Pubkey },
+    Platform,
+}
+
+/// Execute `command`, signing with `signer` and writing output to `out`.
+pub fn run(chain: &mut dyn Chain, signer: &Keypair, command: Command, out: &mut dyn Write) -> Result<()> {
+    let me = signer.pubkey();
+    match command {
+        Command::InitPlatform {
+            fee_bps,
+            review_window_secs,
+        } => {
+            let sig = chain.send(&[ix::initialize(me, fee_bps, review_window_secs)], &[signer])?;
+            writeln!(out, "Platform: {}", pda::platform().0)?;
+            writeln!(out, "Signature: {sig}")?;
+        }
+        Command::CreateCategory { id, name } => {
+            let sig = chain.send(&[ix::create_category(me, me, id, name)], &[signer])?;
+            writeln!(out, "Category: {}", pda::category(id).0)?;
+            writeln!(out, "Signature: {sig}")?;
+        }
+        Command::UpdateCategory { id, name, active } => {
+            let sig = chain.send(&[ix::update_category(me, id, name, active)], &[signer])?;
+            writeln!(out, "Signature: {sig}")?;
+        }
+        Command::ListCategories => {
+            let mut categories = chain
+                .get_program_accounts(Category::DISCRIMINATOR)?
+                .into_iter()
+                .map(|(_, account)| Ok(arcadium_client::decode_category(&account.data)?))
+                .collect::<Result<Vec<_>>>()?;
+            categories.sort_by_key(|category| category.category_id);
+            for category in &categories {
+                let inactive = if category.is_active { "" } else { "  (inactive)" };
+                writeln!(
+                    out,
+                    "{:>5}  {} ({} agents){inactive}",
+                    category.category_id, category.name, category.agent_count,
+                )?;
+            }
+            writeln!(out, "{} categor{}", categories.len(), if categories.len() == 1 { "y" } else { "ies" })?;
+        }
+        Command::RegisterAgent {
+            name,
+            skill_uri,
+            price,
+            mint,
+            metadata,
+        } => {
+            let agent_id = match chain.get_account(&pda::owner_registry(&me).0)? {
+                Some(account) => arcadium_client::decode_owner_registry(&account.data)?.agents_created,
+                None => 0,
+            };
+            let mint = mint.map(|mint| payment_mint(chain, mint)).transpose()?;
+            let metadata = metadata.apply(AgentMetadataInput::default());
+            let instruction = ix::register_agent(me, agent_id, name, skill_uri, price, metadata, mint);
+            let sig = chain.send(&[instruction], &[signer])?;
+            writeln!(out, "Agent: {}", pda::agent(&me, agent_id).0)?;
+            writeln!(out, "Signature: {sig}")?;
+        }
+        Command::UpdateAgent {
+            agent,
+            name,
+            skill_uri,
+            price,
+            active,
+            metadata,
+        } => {
+            let mut instructions = Vec::new();
+            let listing_changed = name.is_some() || skill_uri.is_some() || price.is_some() || active.is_some();
+            if listing_changed || metadata.is_empty() {
+                instructions.push(ix::update_agent(me, agent, name, skill_uri, price, active));
+            }
+            if !metadata.is_empty() {
+                let current: AgentMetadata = fetch(chain, &pda::agent_metadata(&agent).0)?;
+                let previous_categories = current.categories.clone();
+                let metadata = metadata.apply(AgentMetadataInput {
+                    endpoint_url: current.endpoint_url,
+                    description: current.description,
+                    tags: current.tags,
+                    categories: current.categories,
+                    encryption_key: current.encryption_key,
+                });
+                instructions.push(ix::update_agent_metadata(me, agent, &previous_categories, metadata));
+            }
+            let sig = chain.send(&instructions, &[signer])?;
+            writeln!(out, "Signature: {sig}")?;
+        }
+        Command::ListJobs { agent, client } => {
+            let mut jobs = chain
+                .get_program_accounts(Job::DISCRIMINATOR)?
+                .into_iter()
+                .map(|(address, account)| Ok((address, arcadium_client::decode_job(&account.data)?)))
+                .collect::<Result<Vec<_>>>()?;
+            jobs.retain(|(_, job)| {
+                agent.is_none_or(|agent| job.agent == agent) && client.is_none_or(|client| job.client == client)
+            });
+            jobs.sort_by_key(|(_, job)| (job.agent, job.job_id));
+            for (address, job) in &jobs {
+                writeln!(
+                    out,
+                    "{address}  agent={}  #{}  {}  price={}  client={}",
+                    job.agent,
+                    job.job_id,
+                    job_status_name(&job.status),
+                    job.price_lamports,
+                    job.client,
+                )?;
+            }
+            writeln!(out, "{} job(s)", jobs.len())?;
+        }
+        Command::CompleteJob { job: address } => {
+            let job: Job = fetch(chain, &address)?;
+            let mint = job.mint.map(|mint| payment_mint(chain, mint)).transpose()?;
+            let sig = chain.send(&[ix::complete_job(me, job.agent, address, mint)], &[signer])?;
+            writeln!(out, "Signature: {sig}")?;
+        }
+        Command::Rate {
+            job: address,
+            rating,
+            comment,
+        } => {
+            let job: Job = fetch(chain, &address)?;
+            let sig = chain.send(&[ix::rate_job(me, job.agent, address, rating, comment)], &[signer])?;
+            writeln!(out, "Review: {}", pda::review(&address).0)?;
+            writeln!(out, "Signature: {sig}")?;
+        }
+        Command::Withdraw { agent: address, amount } => {
+            let agent: Agent = fetch(chain, &address)?;
+            let mint = agent.payment_mint.map(|mint| payment_mint(chain, mint)).transpose()?;
+            let sig = chain.send(&[ix::withdraw_earnings(me, address, amount, mint)], &[signer])?;
+            writeln!(out, "Signature: {sig}")?;
+        }
+        Command::Show(Show::Agent { address }) => {
+            print_agent(out, &address, &fetch(chain, &address)?)?;
+            // Agents registered before metadata existed have none
+            let metadata_address = pda::agent_metadata(&address).0;
+            if chain.get_account(&metadata_address)?.is_some() {
+                print_agent_metadata(out, &fetch(chain, &metadata_address)?)?;
+            }
+        }
+        Command::Show(Show::Job { address }) => print_job(out, &address, &fetch(chain, &address)?)?,
+        Command::Show(Show::Platform) => {
+            let address = pda::platform().0;
+            print_platform(out, &address, &fetch(chain, &address)?)?
+        }
+    }
+    Ok(())
+}
+
+/// Read and decode the program account at `address`.
+fn fetch<T: anchor_lang::AccountDeserialize>(chain: &dyn Chain, address: &Pubkey) -> Result<T> {
+    let account = chain
+        .get_account(address)?
+        .ok_or_else(|| anyhow!("account {address} not found"))?;
+    if account.owner != arcadium_client::ID {
+        bail!("account {address} is not owned by the Arcadium program");
+    }
+    arcadium_client::decode(&account.data).with_context(|| format!("decoding {address}"))
+}
+
+/// Pair `mint` with the token program that owns it.
+fn payment_mint(chain: &dyn Chain, mint: Pubkey) -> Result<PaymentMint> {
+    let account = chain
+        .get_account(&mint)?
+        .ok_or_else(|| anyhow!("mint {mint} not found"))?;
+    Ok(PaymentMint {
+        mint,
+        token_program: account.owner,
+    })
+}
+
+fn print_agent(out: &mut dyn Write, address: &Pubkey, agent: &Agent) -> Result<()> {
+    writeln!(out, "Agent {address}")?;
+    writeln!(out, "  name:            {}", agent.name)?;
+    writeln!(out, "  skill_uri:       {}", agent.skill_uri)?;
+    writeln!(out, "  owner:           {}", agent.owner)?;
+    writeln!(out, "  creator:         {}", agent.creator)?;
+    writeln!(out, "  operator:        {}", optional(agent.operator))?;
+    writeln!(out, "  pending_owner:   {}", optional(agent.pending_owner))?;
+    writeln!(out, "  agent_id:        {}", agent.agent_id)?;
+    writeln!(out, "  price:           {}", agent.price_lamports)?;
+    writeln!(out, "  payment_mint:    {}", agent.payment_mint.map_or("SOL".to_string(), |m| m.to_string()))?;
+    writeln!(out, "  active:          {}", agent.is_active)?;
+    writeln!(out, "  jobs_created:    {}", agent.jobs_created)?;
+    writeln!(out, "  open_jobs:       {}", agent.open_jobs)?;
+    writeln!(out, "  jobs_completed:  {}", agent.jobs_completed)?;
+    writeln!(out, "  total_earned:    {}", agent.total_earned)?;
+    if agent.rating_count > 0 {
+        let average = agent.rating_sum as f64 / agent.rating_count as f64;
+        writeln!(out, "  rating:          {average:.2} ({} reviews)", agent.rating_count)?;
+    } else {
+        writeln!(out, "  rating:          -")?;
+    }
+    writeln!(out, "  deadlines_missed: {}", agent.deadlines_missed)?;
+    writeln!(out, "  disputes_resolved: {}", agent.disputes_resolved)?;
+    Ok(())
+}
+
+fn print_agent_metadata(out: &mut dyn Write, metadata: &AgentMetadata) -> Result<()> {
+    writeln!(out, "  endpoint:        {}", metadata.endpoint_url)?;
+    writeln!(out, "  description:     {}", metadata.description)?;
+    writeln!(out, "  tags:            {}", metadata.tags.join(", "))?;
+    let categories: Vec<String> = metadata.categories.iter().map(u16::to_string).collect();
+    writeln!(out, "  categories:      {}", categories.join(", "))?;
+    writeln!(out, "  encryption_key:  {}", optional(metadata.encryption_key.map(hex::encode)))?;
+    Ok(())
+}
+
+fn print_job(out: &mut dyn Write, address: &Pubkey, job: &Job) -> Result<()> {
+    writeln!(out, "Job {address}")?;
+    writeln!(out, "  agent:           {}", job.agent)?;
+    writeln!(out, "  job_id:          {}", job.job_id)?;
+    writeln!(out, "  client:          {}", job.client)?;
+    writeln!(out, "  status:          {}", job_status_name(&job.status))?;
+    writeln!(out, "  task:            {}", job.task_description)?;
+    writeln!(out, "  price:           {}", job.price_lamports)?;
+    writeln!(out, "  mint:            {}", job.mint.map_or("SOL".to_string(), |m| m.to_string()))?;
+    writeln!(out, "  platform_fee_bps: {}", job.platform_fee_bps)?;
+    writeln!(out, "  escrowed:        {}", job.escrowed_amount)?;
+    writeln!(out, "  created_at:      {}", job.created_at)?;
+    writeln!(out, "  deadline:        {}", optional(job.deadline))?;
+    writeln!(out, "  deliverable_uri: {}", job.deliverable_uri)?;
+    writeln!(out, "  delivered_at:    {}", optional(job.delivered_at))?;
+    writeln!(out, "  review_ends_at:  {}", optional(job.review_ends_at))?;
+    writeln!(out, "  completed_at:    {}", optional(job.completed_at))?;
+    writeln!(out, "  rated:           {}", job.rated)?;
+    Ok(())
+}
+
+fn print_platform(out: &mut dyn Write, address: &Pubkey, platform: &Platform) -> Result<()> {
+    writeln!(out, "Platform {address}")?;
+    writeln!(out, "  authority:       {}", platform.authority)?;
+    writeln!(out, "  pending_authority: {}", optional(platform.pending_authority))?;
+    writeln!(out, "  arbiter:         {}", platform.arbiter)?;
+    writeln!(out, "  fee_bps:         {}", platform.platform_fee_bps)?;
+    if let Some(pending) = platform.pending_fee_bps {
+        writeln!(out, "  pending_fee_bps: {pending} (from {})", platform.pending_fee_effective_at)?;
+    }
+    writeln!(out, "  review_window:   {}s", platform.review_window_secs)?;
+    writeln!(out, "  paused:          {}", platform.paused)?;
+    writeln!(out, "  total_jobs:      {}", platform.total_jobs)?;
+    writeln!(out, "  total_volume:    {}", platform.total_volume)?;
+    writeln!(out, "  fees_collected:  {}", platform.fees_collected)?;
+    writeln!(out, "  fees_withdrawn:  {}", platform.fees_withdrawn)?;
+    Ok(())
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or("-".to_string(), |v| v.to_string())
+}
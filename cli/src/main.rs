@@ -0,0 +1,20 @@
+use anyhow::{anyhow, Result};
+use arcadium_cli::{run, Cli, RpcChain};
+use clap::Parser;
+use solana_sdk::signature::read_keypair_file;
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let keypair_path = expand_home(&cli.keypair);
+    let signer = read_keypair_file(&keypair_path)
+        .map_err(|e| anyhow!("reading keypair {keypair_path}: {e}"))?;
+    let mut chain = RpcChain::new(cli.url);
+    run(&mut chain, &signer, cli.command, &mut std::io::stdout())
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    }
+}
@@ -0,0 +1,230 @@
+//! End-to-end CLI tests against LiteSVM.
+//!
+//! Needs the program built first:
+//!   anchor build
+//!   cargo test -p arcadium-cli
+
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use arcadium_cli::{run, AccountData, Chain, Cli};
+use arcadium_client::{instructions as ix, pda};
+use clap::Parser;
+use litesvm::LiteSVM;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk_3::{
+    instruction::{AccountMeta as SvmAccountMeta, Instruction as SvmInstruction},
+    pubkey::Pubkey as SvmPubkey,
+    signature::Keypair as SvmKeypair,
+    transaction::Transaction as SvmTransaction,
+};
+
+/// [`Chain`] over an in-process LiteSVM. LiteSVM has no program-account
+/// scan, so every address a sent instruction touched is remembered and
+/// checked instead.
+struct LiteSvmChain {
+    svm: LiteSVM,
+    seen: BTreeSet<Pubkey>,
+}
+
+fn svm_key(key: &Pubkey) -> SvmPubkey {
+    SvmPubkey::new_from_array(key.to_bytes())
+}
+
+impl LiteSvmChain {
+    fn new() -> Result<Self> {
+        let so_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("target")
+            .join("deploy")
+            .join("arcadium.so");
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(svm_key(&arcadium_client::ID), so_path)?;
+        Ok(Self {
+            svm,
+            seen: BTreeSet::new(),
+        })
+    }
+
+    fn airdrop(&mut self, key: &Pubkey, lamports: u64) -> Result<()> {
+        self.svm
+            .airdrop(&svm_key(key), lamports)
+            .map_err(|e| anyhow!("airdrop failed: {e:?}"))?;
+        Ok(())
+    }
+}
+
+impl Chain for LiteSvmChain {
+    fn get_account(&self, address: &Pubkey) -> Result<Option<AccountData>> {
+        Ok(self.svm.get_account(&svm_key(address)).map(|account| AccountData {
+            owner: Pubkey::new_from_array(account.owner.to_bytes()),
+            lamports: account.lamports,
+            data: account.data,
+        }))
+    }
+
+    fn get_program_accounts(&self, discriminator: &[u8]) -> Result<Vec<(Pubkey, AccountData)>> {
+        let mut accounts = Vec::new();
+        for address in &self.seen {
+            if let Some(account) = self.get_account(address)? {
+                if account.owner == arcadium_client::ID && account.data.starts_with(discriminator) {
+                    accounts.push((*address, account));
+                }
+            }
+        }
+        Ok(accounts)
+    }
+
+    fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<String> {
+        let instructions: Vec<SvmInstruction> = instructions
+            .iter()
+            .map(|ix| SvmInstruction {
+                program_id: svm_key(&ix.program_id),
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .map(|meta| SvmAccountMeta {
+                        pubkey: svm_key(&meta.pubkey),
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                    .collect(),
+                data: ix.data.clone(),
+            })
+            .collect();
+        let signers = signers
+            .iter()
+            .map(|kp| SvmKeypair::try_from(&kp.to_bytes()[..]).map_err(|e| anyhow!("{e}")))
+            .collect::<Result<Vec<_>>>()?;
+        let signer_refs: Vec<&SvmKeypair> = signers.iter().collect();
+
+        let tx = SvmTransaction::new_signed_with_payer(
+            &instructions,
+            Some(&solana_sdk_3::signer::Signer::pubkey(&signers[0])),
+            &signer_refs,
+            self.svm.latest_blockhash(),
+        );
+        let meta = self
+            .svm
+            .send_transaction(tx)
+            .map_err(|e| anyhow!("transaction failed: {:?}", e.err))?;
+
+        for ix in &instructions {
+            self.seen
+                .extend(ix.accounts.iter().map(|meta| Pubkey::new_from_array(meta.pubkey.to_bytes())));
+        }
+        Ok(meta.signature.to_string())
+    }
+}
+
+/// Parse `args` as the CLI would and run the command, returning its output.
+fn arcadium(chain: &mut LiteSvmChain, signer: &Keypair, args: &[&str]) -> Result<String> {
+    let cli = Cli::try_parse_from(std::iter::once("arcadium").chain(args.iter().copied()))?;
+    let mut out = Vec::new();
+    run(chain, signer, cli.command, &mut out)?;
+    Ok(String::from_utf8(out)?)
+}
+
+#[test]
+fn cli_drives_agent_and_job_lifecycle() -> Result<()> {
+    let mut chain = LiteSvmChain::new()?;
+    let authority = Keypair::new();
+    let owner = Keypair::new();
+    let client = Keypair::new();
+    for keypair in [&authority, &owner, &client] {
+        chain.airdrop(&keypair.pubkey(), 10_000_000_000)?;
+    }
+
+    arcadium(&mut chain, &authority, &["init-platform", "--fee-bps", "1000"])?;
+    arcadium(&mut chain, &authority, &["create-category", "--id", "1", "--name", "Email writing"])?;
+    arcadium(&mut chain, &authority, &["create-category", "--id", "2", "--name", "Scheduling"])?;
+    let out = arcadium(
+        &mut chain,
+        &owner,
+        &[
+            "register-agent", "--name", "Sarah", "--skill-uri", "ipfs://example", "--price", "10000000",
+            "--endpoint", "https://sarah.example/webhook", "--tag", "email", "--tag", "writing",
+            "--category", "1",
+        ],
+    )?;
+    let agent = pda::agent(&owner.pubkey(), 0).0;
+    assert!(out.contains(&format!("Agent: {agent}")));
+
+    let out = arcadium(&mut chain, &owner, &["list-categories"])?;
+    assert!(out.contains("1  Email writing (1 agents)"));
+    assert!(out.contains("2  Scheduling (0 agents)"));
+
+    arcadium(
+        &mut chain,
+        &owner,
+        &[
+            "update-agent", "--agent", &agent.to_string(), "--price", "20000000", "--description", "Professional emails",
+            "--category", "1", "--category", "2",
+        ],
+    )?;
+    arcadium(&mut chain, &authority, &["update-category", "--id", "2", "--active", "false"])?;
+    let out = arcadium(&mut chain, &owner, &["list-categories"])?;
+    assert!(out.contains("2  Scheduling (1 agents)  (inactive)"));
+
+    // The client side has no CLI commands; drive it with the builders.
+    let job = pda::job(&agent, 0).0;
+    chain.send(
+        &[ix::create_job(client.pubkey(), agent, 0, "Decline meeting".to_string(), None, None)],
+        &[&client],
+    )?;
+    chain.send(
+        &[ix::submit_deliverable(owner.pubkey(), agent, job, [7u8; 32], "ipfs://result".to_string())],
+        &[&owner],
+    )?;
+
+    let out = arcadium(&mut chain, &owner, &["list-jobs", "--agent", &agent.to_string()])?;
+    assert!(out.contains(&job.to_string()));
+    assert!(out.contains("delivered"));
+    assert!(out.contains("1 job(s)"));
+    let out = arcadium(&mut chain, &owner, &["list-jobs", "--client", &owner.pubkey().to_string()])?;
+    assert!(out.contains("0 job(s)"));
+
+    arcadium(&mut chain, &client, &["complete-job", "--job", &job.to_string()])?;
+    arcadium(&mut chain, &client, &["rate", "--job", &job.to_string(), "--rating", "5", "--comment", "Great"])?;
+
+    let out = arcadium(&mut chain, &owner, &["show", "job", &job.to_string()])?;
+    assert!(out.contains("completed"));
+    assert!(out.contains("rated:           true"));
+
+    let out = arcadium(&mut chain, &owner, &["show", "agent", &agent.to_string()])?;
+    assert!(out.contains("price:           20000000"));
+    assert!(out.contains("jobs_completed:  1"));
+    assert!(out.contains("total_earned:    18000000"));
+    assert!(out.contains("rating:          5.00 (1 reviews)"));
+    assert!(out.contains("endpoint:        https://sarah.example/webhook"));
+    assert!(out.contains("description:     Professional emails"));
+    assert!(out.contains("tags:            email, writing"));
+    assert!(out.contains("categories:      1, 2"));
+
+    // Tags and categories can be emptied without touching other fields.
+    arcadium(&mut chain, &owner, &["update-agent", "--agent", &agent.to_string(), "--clear-tags", "--clear-categories"])?;
+    let out = arcadium(&mut chain, &owner, &["show", "agent", &agent.to_string()])?;
+    assert!(out.contains("tags:            \n"));
+    assert!(out.contains("categories:      \n"));
+    assert!(out.contains("endpoint:        https://sarah.example/webhook"));
+    let out = arcadium(&mut chain, &owner, &["list-categories"])?;
+    assert!(out.contains("1  Email writing (0 agents)"));
+    assert!(out.contains("2  Scheduling (0 agents)"));
+    assert!(arcadium(&mut chain, &owner, &["update-agent", "--agent", &agent.to_string(), "--tag", "x", "--clear-tags"]).is_err());
+
+    let vault = pda::agent_vault(&agent).0;
+    let vault_before = chain.get_account(&vault)?.unwrap().lamports;
+    arcadium(&mut chain, &owner, &["withdraw", "--agent", &agent.to_string(), "--amount", "10000000"])?;
+    assert_eq!(chain.get_account(&vault)?.unwrap().lamports, vault_before - 10_000_000);
+
+    let out = arcadium(&mut chain, &owner, &["show", "platform"])?;
+    assert!(out.contains(&format!("authority:       {}", authority.pubkey())));
+    assert!(out.contains("fees_collected:  2000000"));
+
+    // Only the owner may withdraw.
+    assert!(arcadium(&mut chain, &client, &["withdraw", "--agent", &agent.to_string(), "--amount", "1"]).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,554 @@
+//! Instruction builders. Each fills in every account the program expects
+//! (PDAs, token accounts, programs) from the few keys only the caller knows.
+//!
+//! Builders for instructions that move funds take an optional
+//! [`PaymentMint`]; pass the job's or agent's mint for token-priced work and
+//! `None` for SOL. Wallet-side token accounts are taken to be the wallet's
+//! associated token account.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token;
+use arcadium::AgentMetadataInput;
+
+use crate::pda;
+
+/// SPL mint something is priced in, with the token program that owns it
+/// (classic Token or Token-2022).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PaymentMint {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl PaymentMint {
+    /// `owner`'s associated token account for this mint.
+    pub fn ata(&self, owner: &Pubkey) -> Pubkey {
+        pda::associated_token(owner, &self.mint, &self.token_program)
+    }
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: arcadium::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Append the writable `Category` accounts for `category_ids`, which the
+/// program takes as remaining accounts. `build` has already put the program
+/// ID in every unused optional slot, so they can't be mistaken for those.
+fn with_categories<'a>(mut ix: Instruction, category_ids: impl IntoIterator<Item = &'a u16>) -> Instruction {
+    for &category_id in category_ids {
+        let category = pda::category(category_id).0;
+        if !ix.accounts.iter().any(|meta| meta.pubkey == category) {
+            ix.accounts.push(AccountMeta::new(category, false));
+        }
+    }
+    ix
+}
+
+// ============================================================================
+// Platform
+// ============================================================================
+
+pub fn initialize(authority: Pubkey, platform_fee_bps: u16, review_window_secs: i64) -> Instruction {
+    build(
+        arcadium::accounts::Initialize {
+            platform: pda::platform().0,
+            authority,
+            treasury: pda::treasury().0,
+            system_program: system_program::ID,
+        },
+        arcadium::instruction::Initialize {
+            platform_fee_bps,
+            review_window_secs,
+        },
+    )
+}
+
+pub fn set_arbiter(authority: Pubkey, arbiter: Pubkey) -> Instruction {
+    build(
+        arcadium::accounts::SetArbiter {
+            platform: pda::platform().0,
+            authority,
+        },
+        arcadium::instruction::SetArbiter { arbiter },
+    )
+}
+
+pub fn set_paused(authority: Pubkey, paused: bool) -> Instruction {
+    build(
+        arcadium::accounts::SetPaused {
+            platform: pda::platform().0,
+            authority,
+        },
+        arcadium::instruction::SetPaused { paused },
+    )
+}
+
+/// Create category `category_id`; `payer` funds its rent.
+pub fn create_category(authority: Pubkey, payer: Pubkey, category_id: u16, name: String) -> Instruction {
+    build(
+        arcadium::accounts::CreateCategory {
+            platform: pda::platform().0,
+            category: pda::category(category_id).0,
+            authority,
+            payer,
+            system_program: system_program::ID,
+        },
+        arcadium::instruction::CreateCategory { category_id, name },
+    )
+}
+
+pub fn update_category(
+    authority: Pubkey,
+    category_id: u16,
+    name: Option<String>,
+    is_active: Option<bool>,
+) -> Instruction {
+    build(
+        arcadium::accounts::UpdateCategory {
+            platform: pda::platform().0,
+            category: pda::category(category_id).0,
+            authority,
+        },
+        arcadium::instruction::UpdateCategory { name, is_active },
+    )
+}
+
+pub fn propose_authority(authority: Pubkey, new_authority: Pubkey) -> Instruction {
+    build(
+        arcadium::accounts::ProposeAuthority {
+            platform: pda::platform().0,
+            authority,
+        },
+        arcadium::instruction::ProposeAuthority { new_authority },
+    )
+}
+
+pub fn accept_authority(new_authority: Pubkey) -> Instruction {
+    build(
+        arcadium::accounts::AcceptAuthority {
+            platform: pda::platform().0,
+            new_authority,
+        },
+        arcadium::instruction::AcceptAuthority {},
+    )
+}
+
+pub fn update_platform_config(
+    authority: Pubkey,
+    platform_fee_bps: Option<u16>,
+    review_window_secs: Option<i64>,
+) -> Instruction {
+    build(
+        arcadium::accounts::UpdatePlatformConfig {
+            platform: pda::platform().0,
+            authority,
+        },
+        arcadium::instruction::UpdatePlatformConfig {
+            platform_fee_bps,
+            review_window_secs,
+        },
+    )
+}
+
+pub fn withdraw_platform_fees(
+    authority: Pubkey,
+    recipient: Pubkey,
+    amount: u64,
+    mint: Option<PaymentMint>,
+) -> Instruction {
+    let treasury = pda::treasury().0;
+    build(
+        arcadium::accounts::WithdrawPlatformFees {
+            platform: pda::platform().0,
+            treasury,
+            authority,
+            recipient,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            treasury_token: mint.map(|m| m.ata(&treasury)),
+            recipient_token: mint.map(|m| m.ata(&recipient)),
+            token_program: mint.map(|m| m.token_program),
+        },
+        arcadium::instruction::WithdrawPlatformFees { amount },
+    )
+}
+
+// ============================================================================
+// Agents
+// ============================================================================
+
+/// Register `owner`'s next agent. `agent_id` must be the owner registry's
+/// current `agents_created` (0 for a wallet's first agent).
+pub fn register_agent(
+    owner: Pubkey,
+    agent_id: u64,
+    name: String,
+    skill_uri: String,
+    price_lamports: u64,
+    metadata: AgentMetadataInput,
+    mint: Option<PaymentMint>,
+) -> Instruction {
+    let agent = pda::agent(&owner, agent_id).0;
+    let agent_vault = pda::agent_vault(&agent).0;
+    let treasury = pda::treasury().0;
+    let categories = metadata.categories.clone();
+    let ix = build(
+        arcadium::accounts::RegisterAgent {
+            owner_registry: pda::owner_registry(&owner).0,
+            agent,
+            agent_metadata: pda::agent_metadata(&agent).0,
+            agent_vault,
+            platform: pda::platform().0,
+            owner,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            agent_vault_token: mint.map(|m| m.ata(&agent_vault)),
+            token_program: mint.map(|m| m.token_program),
+            associated_token_program: mint.map(|_| associated_token::ID),
+            treasury: mint.map(|_| treasury),
+            treasury_token: mint.map(|m| m.ata(&treasury)),
+        },
+        arcadium::instruction::RegisterAgent {
+            name,
+            skill_uri,
+            price_lamports,
+            metadata,
+        },
+    );
+    with_categories(ix, &categories)
+}
+
+pub fn update_agent(
+    owner: Pubkey,
+    agent: Pubkey,
+    name: Option<String>,
+    skill_uri: Option<String>,
+    price_lamports: Option<u64>,
+    is_active: Option<bool>,
+) -> Instruction {
+    build(
+        arcadium::accounts::UpdateAgent { agent, owner },
+        arcadium::instruction::UpdateAgent {
+            name,
+            skill_uri,
+            price_lamports,
+            is_active,
+        },
+    )
+}
+
+/// Replace `agent`'s metadata; the account is resized to fit.
+/// `previous_categories` are the agent's current `AgentMetadata.categories`.
+pub fn update_agent_metadata(
+    owner: Pubkey,
+    agent: Pubkey,
+    previous_categories: &[u16],
+    metadata: AgentMetadataInput,
+) -> Instruction {
+    let categories: Vec<u16> = previous_categories.iter().chain(&metadata.categories).copied().collect();
+    let ix = build(
+        arcadium::accounts::UpdateAgentMetadata {
+            agent,
+            agent_metadata: pda::agent_metadata(&agent).0,
+            owner,
+            system_program: system_program::ID,
+        },
+        arcadium::instruction::UpdateAgentMetadata { metadata },
+    );
+    with_categories(ix, &categories)
+}
+
+pub fn set_agent_operator(owner: Pubkey, agent: Pubkey, operator: Option<Pubkey>) -> Instruction {
+    build(
+        arcadium::accounts::SetAgentOperator { agent, owner },
+        arcadium::instruction::SetAgentOperator { operator },
+    )
+}
+
+pub fn propose_agent_owner(owner: Pubkey, agent: Pubkey, new_owner: Pubkey) -> Instruction {
+    build(
+        arcadium::accounts::ProposeAgentOwner { agent, owner },
+        arcadium::instruction::ProposeAgentOwner { new_owner },
+    )
+}
+
+pub fn accept_agent_owner(new_owner: Pubkey, agent: Pubkey) -> Instruction {
+    build(
+        arcadium::accounts::AcceptAgentOwner { agent, new_owner },
+        arcadium::instruction::AcceptAgentOwner {},
+    )
+}
+
+pub fn withdraw_earnings(
+    owner: Pubkey,
+    agent: Pubkey,
+    amount: u64,
+    mint: Option<PaymentMint>,
+) -> Instruction {
+    let agent_vault = pda::agent_vault(&agent).0;
+    build(
+        arcadium::accounts::WithdrawEarnings {
+            agent,
+            agent_vault,
+            owner,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            agent_vault_token: mint.map(|m| m.ata(&agent_vault)),
+            owner_token: mint.map(|m| m.ata(&owner)),
+            token_program: mint.map(|m| m.token_program),
+        },
+        arcadium::instruction::WithdrawEarnings { amount },
+    )
+}
+
+/// Close an agent. `creator` is `Agent.creator`, whose registry tracks it;
+/// `categories` are its `AgentMetadata.categories`.
+pub fn close_agent(
+    owner: Pubkey,
+    creator: Pubkey,
+    agent: Pubkey,
+    categories: &[u16],
+    mint: Option<PaymentMint>,
+) -> Instruction {
+    let agent_vault = pda::agent_vault(&agent).0;
+    let ix = build(
+        arcadium::accounts::CloseAgent {
+            owner_registry: pda::owner_registry(&creator).0,
+            agent,
+            agent_metadata: pda::agent_metadata(&agent).0,
+            agent_vault,
+            owner,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            agent_vault_token: mint.map(|m| m.ata(&agent_vault)),
+            owner_token: mint.map(|m| m.ata(&owner)),
+            token_program: mint.map(|m| m.token_program),
+        },
+        arcadium::instruction::CloseAgent {},
+    );
+    with_categories(ix, categories)
+}
+
+// ============================================================================
+// Jobs
+// ============================================================================
+
+/// Hire `agent`. `job_id` must be the agent's current `jobs_created`.
+pub fn create_job(
+    client: Pubkey,
+    agent: Pubkey,
+    job_id: u64,
+    task_description: String,
+    deadline: Option<i64>,
+    mint: Option<PaymentMint>,
+) -> Instruction {
+    let job = pda::job(&agent, job_id).0;
+    let escrow = pda::escrow(&job).0;
+    build(
+        arcadium::accounts::CreateJob {
+            agent,
+            job,
+            escrow,
+            platform: pda::platform().0,
+            client,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            client_token: mint.map(|m| m.ata(&client)),
+            escrow_token: mint.map(|m| m.ata(&escrow)),
+            token_program: mint.map(|m| m.token_program),
+            associated_token_program: mint.map(|_| associated_token::ID),
+        },
+        arcadium::instruction::CreateJob {
+            task_description,
+            deadline,
+        },
+    )
+}
+
+/// Deliver a job; `signer` is the agent's owner or operator.
+pub fn submit_deliverable(
+    signer: Pubkey,
+    agent: Pubkey,
+    job: Pubkey,
+    content_hash: [u8; 32],
+    uri: String,
+) -> Instruction {
+    build(
+        arcadium::accounts::SubmitDeliverable {
+            job,
+            agent,
+            platform: pda::platform().0,
+            signer,
+        },
+        arcadium::instruction::SubmitDeliverable { content_hash, uri },
+    )
+}
+
+pub fn complete_job(client: Pubkey, agent: Pubkey, job: Pubkey, mint: Option<PaymentMint>) -> Instruction {
+    let escrow = pda::escrow(&job).0;
+    let agent_vault = pda::agent_vault(&agent).0;
+    let treasury = pda::treasury().0;
+    build(
+        arcadium::accounts::CompleteJob {
+            job,
+            agent,
+            escrow,
+            agent_vault,
+            platform: pda::platform().0,
+            treasury,
+            client,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            escrow_token: mint.map(|m| m.ata(&escrow)),
+            agent_vault_token: mint.map(|m| m.ata(&agent_vault)),
+            treasury_token: mint.map(|m| m.ata(&treasury)),
+            token_program: mint.map(|m| m.token_program),
+        },
+        arcadium::instruction::CompleteJob {},
+    )
+}
+
+/// Release a delivered job after its review window; anyone can be `payer`.
+pub fn finalize_job(payer: Pubkey, agent: Pubkey, job: Pubkey, mint: Option<PaymentMint>) -> Instruction {
+    let escrow = pda::escrow(&job).0;
+    let agent_vault = pda::agent_vault(&agent).0;
+    let treasury = pda::treasury().0;
+    build(
+        arcadium::accounts::FinalizeJob {
+            job,
+            agent,
+            escrow,
+            agent_vault,
+            platform: pda::platform().0,
+            treasury,
+            payer,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            escrow_token: mint.map(|m| m.ata(&escrow)),
+            agent_vault_token: mint.map(|m| m.ata(&agent_vault)),
+            treasury_token: mint.map(|m| m.ata(&treasury)),
+            token_program: mint.map(|m| m.token_program),
+        },
+        arcadium::instruction::FinalizeJob {},
+    )
+}
+
+pub fn cancel_job(client: Pubkey, agent: Pubkey, job: Pubkey, mint: Option<PaymentMint>) -> Instruction {
+    let escrow = pda::escrow(&job).0;
+    build(
+        arcadium::accounts::CancelJob {
+            job,
+            agent,
+            escrow,
+            client,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            escrow_token: mint.map(|m| m.ata(&escrow)),
+            client_token: mint.map(|m| m.ata(&client)),
+            token_program: mint.map(|m| m.token_program),
+        },
+        arcadium::instruction::CancelJob {},
+    )
+}
+
+/// Refund a job past its deadline; anyone can be `payer`.
+pub fn expire_job(
+    payer: Pubkey,
+    client: Pubkey,
+    agent: Pubkey,
+    job: Pubkey,
+    mint: Option<PaymentMint>,
+) -> Instruction {
+    let escrow = pda::escrow(&job).0;
+    build(
+        arcadium::accounts::ExpireJob {
+            job,
+            agent,
+            escrow,
+            client,
+            payer,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            escrow_token: mint.map(|m| m.ata(&escrow)),
+            client_token: mint.map(|m| m.ata(&client)),
+            token_program: mint.map(|m| m.token_program),
+        },
+        arcadium::instruction::ExpireJob {},
+    )
+}
+
+pub fn dispute_job(client: Pubkey, job: Pubkey) -> Instruction {
+    build(
+        arcadium::accounts::DisputeJob { job, client },
+        arcadium::instruction::DisputeJob {},
+    )
+}
+
+pub fn resolve_dispute(
+    arbiter: Pubkey,
+    client: Pubkey,
+    agent: Pubkey,
+    job: Pubkey,
+    agent_share_bps: u16,
+    mint: Option<PaymentMint>,
+) -> Instruction {
+    let escrow = pda::escrow(&job).0;
+    let agent_vault = pda::agent_vault(&agent).0;
+    let treasury = pda::treasury().0;
+    build(
+        arcadium::accounts::ResolveDispute {
+            job,
+            agent,
+            escrow,
+            agent_vault,
+            platform: pda::platform().0,
+            treasury,
+            client,
+            arbiter,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            escrow_token: mint.map(|m| m.ata(&escrow)),
+            agent_vault_token: mint.map(|m| m.ata(&agent_vault)),
+            treasury_token: mint.map(|m| m.ata(&treasury)),
+            client_token: mint.map(|m| m.ata(&client)),
+            token_program: mint.map(|m| m.token_program),
+        },
+        arcadium::instruction::ResolveDispute { agent_share_bps },
+    )
+}
+
+pub fn rate_job(client: Pubkey, agent: Pubkey, job: Pubkey, rating: u8, comment: String) -> Instruction {
+    build(
+        arcadium::accounts::RateJob {
+            job,
+            agent,
+            review: pda::review(&job).0,
+            client,
+            system_program: system_program::ID,
+        },
+        arcadium::instruction::RateJob { rating, comment },
+    )
+}
+
+pub fn close_job(client: Pubkey, job: Pubkey, mint: Option<PaymentMint>) -> Instruction {
+    let escrow = pda::escrow(&job).0;
+    build(
+        arcadium::accounts::CloseJob {
+            job,
+            escrow,
+            client,
+            system_program: system_program::ID,
+            mint: mint.map(|m| m.mint),
+            escrow_token: mint.map(|m| m.ata(&escrow)),
+            token_program: mint.map(|m| m.token_program),
+            client_token: mint.map(|m| m.ata(&client)),
+        },
+        arcadium::instruction::CloseJob {},
+    )
+}
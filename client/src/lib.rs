@@ -0,0 +1,65 @@
+//! Rust client for the Arcadium program.
+//!
+//! - [`pda`]: derivations for every PDA seed
+//! - [`instructions`]: typed instruction builders with all accounts filled in
+//! - [`decode`] and friends: account deserialization (discriminator checked)
+//!
+//! Account and event types are re-exported from the program crate.
+
+pub mod instructions;
+pub mod pda;
+
+use anchor_lang::{AccountDeserialize, Result};
+
+pub use arcadium::{
+    Agent, AgentMetadata, AgentMetadataInput, Category, Job, JobStatus, OwnerRegistry, Platform,
+    Review, ID,
+};
+pub use instructions::PaymentMint;
+
+/// Decode raw account data (including the 8-byte discriminator) as `T`.
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_platform(data: &[u8]) -> Result<Platform> {
+    decode(data)
+}
+
+pub fn decode_owner_registry(data: &[u8]) -> Result<OwnerRegistry> {
+    decode(data)
+}
+
+pub fn decode_agent(data: &[u8]) -> Result<Agent> {
+    decode(data)
+}
+
+pub fn decode_agent_metadata(data: &[u8]) -> Result<AgentMetadata> {
+    decode(data)
+}
+
+pub fn decode_category(data: &[u8]) -> Result<Category> {
+    decode(data)
+}
+
+pub fn decode_job(data: &[u8]) -> Result<Job> {
+    decode(data)
+}
+
+pub fn decode_review(data: &[u8]) -> Result<Review> {
+    decode(data)
+}
+
+/// Lower-case name of a job status, as shown by the CLI and stored by the
+/// indexer.
+pub fn job_status_name(status: &JobStatus) -> &'static str {
+    match status {
+        JobStatus::Created => "created",
+        JobStatus::Delivered => "delivered",
+        JobStatus::Completed => "completed",
+        JobStatus::Cancelled => "cancelled",
+        JobStatus::Refunded => "refunded",
+        JobStatus::Disputed => "disputed",
+        JobStatus::Resolved => "resolved",
+    }
+}
@@ -0,0 +1,67 @@
+//! PDA derivations, one per seed in the program.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+/// `["platform"]`
+pub fn platform() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"platform"], &arcadium::ID)
+}
+
+/// `["treasury"]`
+pub fn treasury() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury"], &arcadium::ID)
+}
+
+/// `["category", category_id]`
+pub fn category(category_id: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"category", &category_id.to_le_bytes()], &arcadium::ID)
+}
+
+/// `["owner_registry", owner]`
+pub fn owner_registry(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"owner_registry", owner.as_ref()], &arcadium::ID)
+}
+
+/// `["agent", creator, agent_id]`; `creator` is the wallet that registered
+/// the agent, `agent_id` its registry's `agents_created` at the time.
+pub fn agent(creator: &Pubkey, agent_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"agent", creator.as_ref(), &agent_id.to_le_bytes()],
+        &arcadium::ID,
+    )
+}
+
+/// `["agent_metadata", agent]`
+pub fn agent_metadata(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"agent_metadata", agent.as_ref()], &arcadium::ID)
+}
+
+/// `["agent_vault", agent]`
+pub fn agent_vault(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"agent_vault", agent.as_ref()], &arcadium::ID)
+}
+
+/// `["job", agent, job_id]`; `job_id` is the agent's `jobs_created` at the
+/// time the job was created.
+pub fn job(agent: &Pubkey, job_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"job", agent.as_ref(), &job_id.to_le_bytes()],
+        &arcadium::ID,
+    )
+}
+
+/// `["escrow", job]`
+pub fn escrow(job: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"escrow", job.as_ref()], &arcadium::ID)
+}
+
+/// `["review", job]`
+pub fn review(job: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"review", job.as_ref()], &arcadium::ID)
+}
+
+/// Associated token account of `owner` for `mint` under `token_program`.
+pub fn associated_token(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
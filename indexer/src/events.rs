@@ -0,0 +1,109 @@
+//! Program events recovered from transaction logs.
+//!
+//! Anchor's `emit!` logs `Program data: <base64>` where the payload is the
+//! event discriminator followed by its Borsh fields. Only lines logged while
+//! the Arcadium program is the innermost running program are considered.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use arcadium::{
+    AgentClosed, AgentMetadataUpdated, AgentOperatorSet, AgentOwnerProposed,
+    AgentOwnerTransferred, AgentRegistered, AgentUpdated, ArbiterSet, AuthorityProposed,
+    AuthorityTransferred, CategoryUpdated, DeliverableSubmitted, DisputeResolved,
+    EarningsWithdrawn, JobCancelled, JobClosed, JobCompleted, JobCreated, JobDisputed,
+    JobExpired, JobRated, PlatformConfigUpdated, PlatformFeesWithdrawn, PlatformInitialized,
+    PlatformPauseSet,
+};
+use base64::Engine;
+
+/// A decoded event with the keys needed to route it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub name: &'static str,
+    pub agent: Option<Pubkey>,
+    pub job: Option<Pubkey>,
+    /// Category IDs the event names (joined, left or edited).
+    pub categories: Vec<u16>,
+    pub timestamp: i64,
+    /// Borsh payload after the discriminator.
+    pub data: Vec<u8>,
+}
+
+fn try_decode<E: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<E> {
+    let mut payload = data.strip_prefix(E::DISCRIMINATOR)?;
+    E::deserialize(&mut payload).ok()
+}
+
+macro_rules! decode_as {
+    ($data:expr, $($event:ident => |$e:ident| ($agent:expr, $job:expr $(, $categories:expr)?)),* $(,)?) => {
+        $(
+            if let Some($e) = try_decode::<$event>($data) {
+                return Some(Event {
+                    name: stringify!($event),
+                    agent: $agent,
+                    job: $job,
+                    categories: [Vec::new() $(, $categories)?].concat(),
+                    timestamp: $e.timestamp,
+                    data: $data[$event::DISCRIMINATOR.len()..].to_vec(),
+                });
+            }
+        )*
+    };
+}
+
+/// Decode one event payload (discriminator included).
+pub fn decode_event(data: &[u8]) -> Option<Event> {
+    decode_as!(data,
+        PlatformInitialized => |e| (None, None),
+        ArbiterSet => |e| (None, None),
+        PlatformPauseSet => |e| (None, None),
+        AuthorityProposed => |e| (None, None),
+        AuthorityTransferred => |e| (None, None),
+        PlatformConfigUpdated => |e| (None, None),
+        PlatformFeesWithdrawn => |e| (None, None),
+        CategoryUpdated => |e| (None, None, vec![e.category_id]),
+        AgentRegistered => |e| (Some(e.agent), None, e.categories.clone()),
+        AgentUpdated => |e| (Some(e.agent), None),
+        AgentMetadataUpdated => |e| (Some(e.agent), None, e.categories.clone()),
+        AgentOperatorSet => |e| (Some(e.agent), None),
+        AgentOwnerProposed => |e| (Some(e.agent), None),
+        AgentOwnerTransferred => |e| (Some(e.agent), None),
+        AgentClosed => |e| (Some(e.agent), None, e.categories.clone()),
+        EarningsWithdrawn => |e| (Some(e.agent), None),
+        JobCreated => |e| (Some(e.agent), Some(e.job)),
+        DeliverableSubmitted => |e| (Some(e.agent), Some(e.job)),
+        JobCompleted => |e| (Some(e.agent), Some(e.job)),
+        JobCancelled => |e| (Some(e.agent), Some(e.job)),
+        JobExpired => |e| (Some(e.agent), Some(e.job)),
+        JobDisputed => |e| (Some(e.agent), Some(e.job)),
+        DisputeResolved => |e| (Some(e.agent), Some(e.job)),
+        JobRated => |e| (Some(e.agent), Some(e.job)),
+        JobClosed => |e| (Some(e.agent), Some(e.job)),
+    );
+    None
+}
+
+/// Events emitted by the Arcadium program in a transaction's log messages.
+pub fn parse_logs(logs: &[String]) -> Vec<Event> {
+    let program = arcadium::ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        if let Some(rest) = line.strip_prefix("Program ") {
+            if let Some(data) = rest.strip_prefix("data: ") {
+                if stack.last() == Some(&program.as_str()) {
+                    if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data) {
+                        events.extend(decode_event(&bytes));
+                    }
+                }
+            } else if let Some((id, tail)) = rest.split_once(' ') {
+                if tail.starts_with("invoke [") {
+                    stack.push(id);
+                } else if tail == "success" || tail.starts_with("failed") {
+                    stack.pop();
+                }
+            }
+        }
+    }
+    events
+}
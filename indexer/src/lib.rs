@@ -0,0 +1,391 @@
+//! Off-chain indexer that materializes Arcadium marketplace state into SQLite.
+//!
+//! State comes from two places:
+//! - [`Indexer::backfill`]: a full snapshot of program accounts (e.g. from
+//!   `getProgramAccounts`), which replaces every state table
+//! - [`Indexer::ingest_transaction`]: a transaction's log messages, whose
+//!   events are recorded and whose touched accounts are returned so the
+//!   caller can refetch them with [`Indexer::ingest_account`] (or
+//!   [`Indexer::remove_account`] once closed)
+//!
+//! Accounts are recognised by their Anchor discriminator; anything else
+//! (owner registries, escrows) is skipped.
+
+pub mod events;
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use arcadium_client::{pda, Agent, AgentMetadata, Category, Job, JobStatus, Platform, Review};
+use rusqlite::{params, Connection, OptionalExtension};
+
+pub use events::{parse_logs, Event};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Sqlite(rusqlite::Error),
+    Decode(Pubkey, anchor_lang::error::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Sqlite(e) => write!(f, "sqlite: {e}"),
+            Error::Decode(address, e) => write!(f, "decoding {address}: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Self {
+        Error::Sqlite(e)
+    }
+}
+
+const STATE_TABLES: [&str; 8] = [
+    "platform",
+    "agents",
+    "agent_metadata",
+    "agent_tags",
+    "agent_categories",
+    "categories",
+    "jobs",
+    "reviews",
+];
+
+pub struct Indexer {
+    conn: Connection,
+}
+
+impl Indexer {
+    /// Open (creating if needed) the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(include_str!("schema.sql"))?;
+        Ok(Self { conn })
+    }
+
+    /// The underlying connection, for queries.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Replace all state with a complete snapshot of program accounts read at
+    /// `slot`. Returns how many accounts were indexed.
+    pub fn backfill(&mut self, accounts: impl IntoIterator<Item = (Pubkey, Vec<u8>)>, slot: u64) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        for table in STATE_TABLES {
+            tx.execute(&format!("DELETE FROM {table}"), [])?;
+        }
+        let mut indexed = 0;
+        for (address, data) in accounts {
+            if write_account(&tx, &address, &data, slot)? {
+                indexed += 1;
+            }
+        }
+        tx.commit()?;
+        Ok(indexed)
+    }
+
+    /// Index one account as read at `slot`, unless a newer read is already
+    /// stored. Returns whether the account type is indexed.
+    pub fn ingest_account(&self, address: &Pubkey, data: &[u8], slot: u64) -> Result<bool> {
+        write_account(&self.conn, address, data, slot)
+    }
+
+    /// Drop a closed account from every state table.
+    pub fn remove_account(&self, address: &Pubkey) -> Result<()> {
+        for table in STATE_TABLES {
+            self.conn
+                .execute(&format!("DELETE FROM {table} WHERE address = ?1"), [address.to_string()])?;
+        }
+        Ok(())
+    }
+
+    /// Record the events in a transaction's logs. Returns the accounts they
+    /// touched, which the caller should refetch.
+    pub fn ingest_transaction(&self, signature: &str, slot: u64, logs: &[String]) -> Result<BTreeSet<Pubkey>> {
+        let mut touched = BTreeSet::new();
+        for (idx, event) in parse_logs(logs).into_iter().enumerate() {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO events (signature, idx, slot, name, agent, job, timestamp, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    signature,
+                    idx as i64,
+                    slot as i64,
+                    event.name,
+                    event.agent.map(|k| k.to_string()),
+                    event.job.map(|k| k.to_string()),
+                    event.timestamp,
+                    event.data,
+                ],
+            )?;
+            // Fees and job totals live on the platform account.
+            touched.insert(pda::platform().0);
+            if let Some(agent) = event.agent {
+                touched.insert(agent);
+                touched.insert(pda::agent_metadata(&agent).0);
+                // Categories the agent is leaving are only known from our copy.
+                if event.name == "AgentMetadataUpdated" {
+                    for category_id in self.agent_categories(&agent)? {
+                        touched.insert(pda::category(category_id).0);
+                    }
+                }
+            }
+            for &category_id in &event.categories {
+                touched.insert(pda::category(category_id).0);
+            }
+            if let Some(job) = event.job {
+                touched.insert(job);
+                if event.name == "JobRated" {
+                    touched.insert(pda::review(&job).0);
+                }
+            }
+        }
+        Ok(touched)
+    }
+
+    /// Category IDs `agent` is indexed under.
+    fn agent_categories(&self, agent: &Pubkey) -> Result<Vec<u16>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT category_id FROM agent_categories WHERE agent = ?1")?;
+        let rows = stmt.query_map([agent.to_string()], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn meta(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| row.get(0))
+            .optional()?)
+    }
+
+    pub fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            [key, value],
+        )?;
+        Ok(())
+    }
+}
+
+fn decode<T: anchor_lang::AccountDeserialize>(address: &Pubkey, data: &[u8]) -> Result<T> {
+    arcadium_client::decode(data).map_err(|e| Error::Decode(*address, e))
+}
+
+/// Whether `table` already holds `address` as read at a later slot.
+fn is_stale(conn: &Connection, table: &str, address: &str, slot: u64) -> Result<bool> {
+    let stored: Option<i64> = conn
+        .query_row(&format!("SELECT slot FROM {table} WHERE address = ?1"), [address], |row| row.get(0))
+        .optional()?;
+    Ok(stored.is_some_and(|stored| stored as u64 > slot))
+}
+
+fn write_account(conn: &Connection, address: &Pubkey, data: &[u8], slot: u64) -> Result<bool> {
+    if data.starts_with(Platform::DISCRIMINATOR) {
+        write_platform(conn, address, &decode(address, data)?, slot)?;
+    } else if data.starts_with(Agent::DISCRIMINATOR) {
+        write_agent(conn, address, &decode(address, data)?, slot)?;
+    } else if data.starts_with(AgentMetadata::DISCRIMINATOR) {
+        write_agent_metadata(conn, address, &decode(address, data)?, slot)?;
+    } else if data.starts_with(Category::DISCRIMINATOR) {
+        write_category(conn, address, &decode(address, data)?, slot)?;
+    } else if data.starts_with(Job::DISCRIMINATOR) {
+        write_job(conn, address, &decode(address, data)?, slot)?;
+    } else if data.starts_with(Review::DISCRIMINATOR) {
+        write_review(conn, address, &decode(address, data)?, slot)?;
+    } else {
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+fn write_platform(conn: &Connection, address: &Pubkey, p: &Platform, slot: u64) -> Result<()> {
+    let key = address.to_string();
+    if is_stale(conn, "platform", &key, slot)? {
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO platform (address, authority, pending_authority, arbiter,
+            platform_fee_bps, pending_fee_bps, review_window_secs, total_jobs, total_volume,
+            fees_collected, fees_withdrawn, paused, slot)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            key,
+            p.authority.to_string(),
+            p.pending_authority.map(|k| k.to_string()),
+            p.arbiter.to_string(),
+            p.platform_fee_bps,
+            p.pending_fee_bps,
+            p.review_window_secs,
+            p.total_jobs as i64,
+            p.total_volume as i64,
+            p.fees_collected as i64,
+            p.fees_withdrawn as i64,
+            p.paused,
+            slot as i64,
+        ],
+    )?;
+    Ok(())
+}
+
+fn write_agent(conn: &Connection, address: &Pubkey, a: &Agent, slot: u64) -> Result<()> {
+    let key = address.to_string();
+    if is_stale(conn, "agents", &key, slot)? {
+        return Ok(());
+    }
+    let avg_rating = (a.rating_count > 0).then(|| a.rating_sum as f64 / a.rating_count as f64);
+    conn.execute(
+        "INSERT OR REPLACE INTO agents (address, owner, creator, operator, agent_id, name,
+            skill_uri, price, payment_mint, is_active, jobs_created, open_jobs, jobs_completed,
+            total_earned, rating_count, rating_sum, avg_rating, deadlines_missed,
+            disputes_resolved, slot)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+            ?18, ?19, ?20)",
+        params![
+            key,
+            a.owner.to_string(),
+            a.creator.to_string(),
+            a.operator.map(|k| k.to_string()),
+            a.agent_id as i64,
+            a.name,
+            a.skill_uri,
+            a.price_lamports as i64,
+            a.payment_mint.map(|k| k.to_string()),
+            a.is_active,
+            a.jobs_created as i64,
+            a.open_jobs as i64,
+            a.jobs_completed as i64,
+            a.total_earned as i64,
+            a.rating_count as i64,
+            a.rating_sum as i64,
+            avg_rating,
+            a.deadlines_missed as i64,
+            a.disputes_resolved as i64,
+            slot as i64,
+        ],
+    )?;
+    Ok(())
+}
+
+fn write_agent_metadata(conn: &Connection, address: &Pubkey, m: &AgentMetadata, slot: u64) -> Result<()> {
+    let key = address.to_string();
+    if is_stale(conn, "agent_metadata", &key, slot)? {
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO agent_metadata (address, agent, endpoint_url, description, encryption_key, slot)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            key,
+            m.agent.to_string(),
+            m.endpoint_url,
+            m.description,
+            m.encryption_key.map(hex::encode),
+            slot as i64,
+        ],
+    )?;
+    conn.execute("DELETE FROM agent_tags WHERE address = ?1", [&key])?;
+    for tag in &m.tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO agent_tags (address, agent, tag) VALUES (?1, ?2, ?3)",
+            params![key, m.agent.to_string(), tag],
+        )?;
+    }
+    conn.execute("DELETE FROM agent_categories WHERE address = ?1", [&key])?;
+    for category_id in &m.categories {
+        conn.execute(
+            "INSERT OR IGNORE INTO agent_categories (address, agent, category_id) VALUES (?1, ?2, ?3)",
+            params![key, m.agent.to_string(), category_id],
+        )?;
+    }
+    Ok(())
+}
+
+fn write_category(conn: &Connection, address: &Pubkey, c: &Category, slot: u64) -> Result<()> {
+    let key = address.to_string();
+    if is_stale(conn, "categories", &key, slot)? {
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO categories (address, category_id, name, is_active, agent_count, slot)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![key, c.category_id, c.name, c.is_active, c.agent_count, slot as i64],
+    )?;
+    Ok(())
+}
+
+fn write_job(conn: &Connection, address: &Pubkey, j: &Job, slot: u64) -> Result<()> {
+    let key = address.to_string();
+    if is_stale(conn, "jobs", &key, slot)? {
+        return Ok(());
+    }
+    let is_open = matches!(j.status, JobStatus::Created | JobStatus::Delivered | JobStatus::Disputed);
+    conn.execute(
+        "INSERT OR REPLACE INTO jobs (address, agent, job_id, client, task_description, price,
+            mint, platform_fee_bps, escrowed_amount, status, is_open, created_at, deadline,
+            deliverable_uri, delivered_at, review_ends_at, completed_at, rated, slot)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+            ?18, ?19)",
+        params![
+            key,
+            j.agent.to_string(),
+            j.job_id as i64,
+            j.client.to_string(),
+            j.task_description,
+            j.price_lamports as i64,
+            j.mint.map(|k| k.to_string()),
+            j.platform_fee_bps,
+            j.escrowed_amount as i64,
+            arcadium_client::job_status_name(&j.status),
+            is_open,
+            j.created_at,
+            j.deadline,
+            j.deliverable_uri,
+            j.delivered_at,
+            j.review_ends_at,
+            j.completed_at,
+            j.rated,
+            slot as i64,
+        ],
+    )?;
+    Ok(())
+}
+
+fn write_review(conn: &Connection, address: &Pubkey, r: &Review, slot: u64) -> Result<()> {
+    let key = address.to_string();
+    if is_stale(conn, "reviews", &key, slot)? {
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO reviews (address, job, agent, client, rating, comment, created_at, slot)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            key,
+            r.job.to_string(),
+            r.agent.to_string(),
+            r.client.to_string(),
+            r.rating,
+            r.comment,
+            r.created_at,
+            slot as i64,
+        ],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! `arcadium-indexer`: keep a SQLite database in sync with the program.
+//!
+//! On first run (or with `--backfill`) every program account is snapshotted;
+//! afterwards new program transactions are polled, their events recorded and
+//! the accounts they touched refetched.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use arcadium_indexer::Indexer;
+use clap::Parser;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+
+const CURSOR_KEY: &str = "last_signature";
+
+#[derive(Parser, Debug)]
+#[command(name = "arcadium-indexer", version, about = "Index Arcadium marketplace state into SQLite")]
+struct Args {
+    /// JSON-RPC endpoint
+    #[arg(long, short = 'u', env = "ARCADIUM_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// SQLite database file
+    #[arg(long, env = "ARCADIUM_INDEXER_DB", default_value = "arcadium.db")]
+    db: String,
+
+    /// Re-snapshot every program account before following new transactions
+    #[arg(long)]
+    backfill: bool,
+
+    /// Seconds between polls for new transactions
+    #[arg(long, default_value_t = 5)]
+    poll_secs: u64,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let rpc = RpcClient::new_with_commitment(args.url, CommitmentConfig::confirmed());
+    let mut indexer = Indexer::open(&args.db)?;
+
+    if args.backfill || indexer.meta(CURSOR_KEY)?.is_none() {
+        // Take the cursor first so nothing landing during the snapshot is missed.
+        let latest = rpc.get_signatures_for_address_with_config(
+            &arcadium_client::ID,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(1),
+                ..Default::default()
+            },
+        )?;
+        let slot = rpc.get_slot()?;
+        let accounts = rpc.get_program_accounts(&arcadium_client::ID)?;
+        let indexed = indexer.backfill(accounts.into_iter().map(|(key, account)| (key, account.data)), slot)?;
+        println!("backfilled {indexed} accounts at slot {slot}");
+        if let Some(latest) = latest.first() {
+            indexer.set_meta(CURSOR_KEY, &latest.signature)?;
+        }
+    }
+
+    loop {
+        // RPC hiccups are retried on the next poll; the cursor only moves past
+        // indexed transactions and re-ingesting one is harmless
+        if let Err(e) = poll(&rpc, &indexer) {
+            eprintln!("poll failed: {e:#}");
+        }
+        std::thread::sleep(Duration::from_secs(args.poll_secs));
+    }
+}
+
+/// Process program transactions newer than the cursor, oldest first.
+fn poll(rpc: &RpcClient, indexer: &Indexer) -> Result<()> {
+    let until = indexer
+        .meta(CURSOR_KEY)?
+        .map(|s| Signature::from_str(&s))
+        .transpose()?;
+
+    // Newest first, paged with `before`
+    let mut pending = Vec::new();
+    let mut before = None;
+    loop {
+        let page = rpc.get_signatures_for_address_with_config(
+            &arcadium_client::ID,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                ..Default::default()
+            },
+        )?;
+        let Some(last) = page.last() else { break };
+        before = Some(Signature::from_str(&last.signature)?);
+        pending.extend(page);
+    }
+
+    for status in pending.into_iter().rev() {
+        if status.err.is_none() {
+            let signature = Signature::from_str(&status.signature)?;
+            let tx = rpc.get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                    ..Default::default()
+                },
+            )?;
+            let logs: Option<Vec<String>> = tx.transaction.meta.and_then(|meta| meta.log_messages.into());
+            let touched = indexer.ingest_transaction(&status.signature, tx.slot, &logs.unwrap_or_default())?;
+            refresh(rpc, indexer, touched)?;
+        }
+        indexer.set_meta(CURSOR_KEY, &status.signature)?;
+    }
+    Ok(())
+}
+
+/// Refetch `addresses`, dropping any that have been closed.
+fn refresh(rpc: &RpcClient, indexer: &Indexer, addresses: BTreeSet<Pubkey>) -> Result<()> {
+    let addresses: Vec<Pubkey> = addresses.into_iter().collect();
+    for chunk in addresses.chunks(100) {
+        let response = rpc.get_multiple_accounts_with_commitment(chunk, rpc.commitment())?;
+        for (address, account) in chunk.iter().zip(response.value) {
+            match account {
+                Some(account) if account.owner == arcadium_client::ID => {
+                    indexer.ingest_account(address, &account.data, response.context.slot)?;
+                }
+                _ => indexer.remove_account(address)?,
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+-- Marketplace state materialized from Arcadium program accounts. Pubkeys are
+-- base58 text; every state row records the slot it was read at so older
+-- snapshots never overwrite newer ones.
+
+CREATE TABLE IF NOT EXISTS platform (
+    address             TEXT PRIMARY KEY,
+    authority           TEXT NOT NULL,
+    pending_authority   TEXT,
+    arbiter             TEXT NOT NULL,
+    platform_fee_bps    INTEGER NOT NULL,
+    pending_fee_bps     INTEGER,
+    review_window_secs  INTEGER NOT NULL,
+    total_jobs          INTEGER NOT NULL,
+    total_volume        INTEGER NOT NULL,
+    fees_collected      INTEGER NOT NULL,
+    fees_withdrawn      INTEGER NOT NULL,
+    paused              INTEGER NOT NULL,
+    slot                INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS agents (
+    address             TEXT PRIMARY KEY,
+    owner               TEXT NOT NULL,
+    creator             TEXT NOT NULL,
+    operator            TEXT,
+    agent_id            INTEGER NOT NULL,
+    name                TEXT NOT NULL,
+    skill_uri           TEXT NOT NULL,
+    price               INTEGER NOT NULL,
+    payment_mint        TEXT,
+    is_active           INTEGER NOT NULL,
+    jobs_created        INTEGER NOT NULL,
+    open_jobs           INTEGER NOT NULL,
+    jobs_completed      INTEGER NOT NULL,
+    total_earned        INTEGER NOT NULL,
+    rating_count        INTEGER NOT NULL,
+    rating_sum          INTEGER NOT NULL,
+    avg_rating          REAL,               -- NULL until the first review
+    deadlines_missed    INTEGER NOT NULL,
+    disputes_resolved   INTEGER NOT NULL,
+    slot                INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS agents_owner ON agents (owner);
+CREATE INDEX IF NOT EXISTS agents_rating ON agents (avg_rating DESC);
+
+CREATE TABLE IF NOT EXISTS agent_metadata (
+    address             TEXT PRIMARY KEY,
+    agent               TEXT NOT NULL,
+    endpoint_url        TEXT NOT NULL,
+    description         TEXT NOT NULL,
+    encryption_key      TEXT,               -- hex
+    slot                INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS agent_metadata_agent ON agent_metadata (agent);
+
+-- One row per tag in an agent's metadata, keyed by the metadata account.
+CREATE TABLE IF NOT EXISTS agent_tags (
+    address             TEXT NOT NULL,
+    agent               TEXT NOT NULL,
+    tag                 TEXT NOT NULL,
+    PRIMARY KEY (address, tag)
+);
+CREATE INDEX IF NOT EXISTS agent_tags_tag ON agent_tags (tag);
+
+-- One row per category ID in an agent's metadata, keyed like agent_tags.
+CREATE TABLE IF NOT EXISTS agent_categories (
+    address             TEXT NOT NULL,
+    agent               TEXT NOT NULL,
+    category_id         INTEGER NOT NULL,
+    PRIMARY KEY (address, category_id)
+);
+CREATE INDEX IF NOT EXISTS agent_categories_category ON agent_categories (category_id);
+
+CREATE TABLE IF NOT EXISTS categories (
+    address             TEXT PRIMARY KEY,
+    category_id         INTEGER NOT NULL UNIQUE,
+    name                TEXT NOT NULL,
+    is_active           INTEGER NOT NULL,
+    agent_count         INTEGER NOT NULL,
+    slot                INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS jobs (
+    address             TEXT PRIMARY KEY,
+    agent               TEXT NOT NULL,
+    job_id              INTEGER NOT NULL,
+    client              TEXT NOT NULL,
+    task_description    TEXT NOT NULL,
+    price               INTEGER NOT NULL,
+    mint                TEXT,
+    platform_fee_bps    INTEGER NOT NULL,
+    escrowed_amount     INTEGER NOT NULL,
+    status              TEXT NOT NULL,
+    is_open             INTEGER NOT NULL,   -- created, delivered or disputed
+    created_at          INTEGER NOT NULL,
+    deadline            INTEGER,
+    deliverable_uri     TEXT NOT NULL,
+    delivered_at        INTEGER,
+    review_ends_at      INTEGER,
+    completed_at        INTEGER,
+    rated               INTEGER NOT NULL,
+    slot                INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS jobs_agent ON jobs (agent, job_id);
+CREATE INDEX IF NOT EXISTS jobs_client ON jobs (client);
+
+CREATE TABLE IF NOT EXISTS reviews (
+    address             TEXT PRIMARY KEY,
+    job                 TEXT NOT NULL,
+    agent               TEXT NOT NULL,
+    client              TEXT NOT NULL,
+    rating              INTEGER NOT NULL,
+    comment             TEXT NOT NULL,
+    created_at          INTEGER NOT NULL,
+    slot                INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS reviews_agent ON reviews (agent);
+
+-- Program events decoded from transaction logs, in log order.
+CREATE TABLE IF NOT EXISTS events (
+    signature           TEXT NOT NULL,
+    idx                 INTEGER NOT NULL,
+    slot                INTEGER NOT NULL,
+    name                TEXT NOT NULL,
+    agent               TEXT,
+    job                 TEXT,
+    timestamp           INTEGER NOT NULL,
+    data                BLOB NOT NULL,      -- Borsh payload after the discriminator
+    PRIMARY KEY (signature, idx)
+);
+CREATE INDEX IF NOT EXISTS events_agent ON events (agent);
+CREATE INDEX IF NOT EXISTS events_job ON events (job);
+
+-- Indexer bookkeeping, e.g. the last processed signature.
+CREATE TABLE IF NOT EXISTS meta (
+    key                 TEXT PRIMARY KEY,
+    value               TEXT NOT NULL
+);
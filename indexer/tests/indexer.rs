@@ -0,0 +1,205 @@
+//! Indexer tests against LiteSVM: follow transaction logs, then check a
+//! snapshot backfill lands on the same state.
+//!
+//! Needs the program built first:
+//!   anchor build
+//!   cargo test -p arcadium-indexer
+
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use arcadium_client::{instructions as ix, pda, AgentMetadataInput};
+use arcadium_indexer::Indexer;
+use litesvm::LiteSVM;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk_3::{
+    instruction::{AccountMeta as SvmAccountMeta, Instruction as SvmInstruction},
+    pubkey::Pubkey as SvmPubkey,
+    signature::Keypair as SvmKeypair,
+    transaction::Transaction as SvmTransaction,
+};
+
+fn svm_key(key: &Pubkey) -> SvmPubkey {
+    SvmPubkey::new_from_array(key.to_bytes())
+}
+
+/// LiteSVM plus everything the indexer would get from a validator: each
+/// transaction's logs, and every address seen so far for snapshots.
+struct Harness {
+    svm: LiteSVM,
+    indexer: Indexer,
+    seen: BTreeSet<Pubkey>,
+    slot: u64,
+}
+
+impl Harness {
+    fn new() -> Result<Self> {
+        let so_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("target")
+            .join("deploy")
+            .join("arcadium.so");
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(svm_key(&arcadium_client::ID), so_path)?;
+        Ok(Self {
+            svm,
+            indexer: Indexer::open_in_memory()?,
+            seen: BTreeSet::new(),
+            slot: 0,
+        })
+    }
+
+    fn funded(&mut self) -> Result<Keypair> {
+        let keypair = Keypair::new();
+        self.svm
+            .airdrop(&svm_key(&keypair.pubkey()), 10_000_000_000)
+            .map_err(|e| anyhow!("airdrop failed: {e:?}"))?;
+        Ok(keypair)
+    }
+
+    /// Land `instruction`, then index it the way the daemon does: record the
+    /// logs and refetch whatever they touched.
+    fn send(&mut self, instruction: Instruction, signer: &Keypair) -> Result<()> {
+        let svm_ix = SvmInstruction {
+            program_id: svm_key(&instruction.program_id),
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| SvmAccountMeta {
+                    pubkey: svm_key(&meta.pubkey),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: instruction.data.clone(),
+        };
+        let svm_signer = SvmKeypair::try_from(&signer.to_bytes()[..])?;
+        let tx = SvmTransaction::new_signed_with_payer(
+            &[svm_ix],
+            Some(&svm_key(&signer.pubkey())),
+            &[&svm_signer],
+            self.svm.latest_blockhash(),
+        );
+        let meta = self
+            .svm
+            .send_transaction(tx)
+            .map_err(|e| anyhow!("transaction failed: {:?}", e.err))?;
+        self.seen.extend(instruction.accounts.iter().map(|meta| meta.pubkey));
+
+        self.slot += 1;
+        let touched = self
+            .indexer
+            .ingest_transaction(&meta.signature.to_string(), self.slot, &meta.logs)?;
+        for address in touched {
+            match self.account_data(&address) {
+                Some(data) => {
+                    self.indexer.ingest_account(&address, &data, self.slot)?;
+                }
+                None => self.indexer.remove_account(&address)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn account_data(&self, address: &Pubkey) -> Option<Vec<u8>> {
+        self.svm
+            .get_account(&svm_key(address))
+            .filter(|account| account.owner == svm_key(&arcadium_client::ID))
+            .map(|account| account.data)
+    }
+
+    fn snapshot(&self) -> Vec<(Pubkey, Vec<u8>)> {
+        self.seen
+            .iter()
+            .filter_map(|address| Some((*address, self.account_data(address)?)))
+            .collect()
+    }
+}
+
+fn query<T: rusqlite::types::FromSql>(indexer: &Indexer, sql: &str) -> Result<T> {
+    Ok(indexer.connection().query_row(sql, [], |row| row.get(0))?)
+}
+
+#[test]
+fn indexer_follows_logs_and_backfills() -> Result<()> {
+    let mut h = Harness::new()?;
+    let authority = h.funded()?;
+    let owner = h.funded()?;
+    let client = h.funded()?;
+
+    let agent = pda::agent(&owner.pubkey(), 0).0;
+    h.send(ix::initialize(authority.pubkey(), 1000, 86_400), &authority)?;
+    let email = ix::create_category(authority.pubkey(), authority.pubkey(), 1, "Email writing".to_string());
+    h.send(email, &authority)?;
+    let metadata = AgentMetadataInput {
+        endpoint_url: "https://sarah.example/webhook".to_string(),
+        tags: vec!["email".to_string(), "writing".to_string()],
+        categories: vec![1],
+        ..Default::default()
+    };
+    h.send(
+        ix::register_agent(owner.pubkey(), 0, "Sarah".to_string(), "ipfs://example".to_string(), 10_000_000, metadata, None),
+        &owner,
+    )?;
+
+    // Two rated jobs (5 and 3) and a third left open.
+    for (job_id, rating) in [(0u64, 5u8), (1, 3)] {
+        let job = pda::job(&agent, job_id).0;
+        h.send(ix::create_job(client.pubkey(), agent, job_id, "Task".to_string(), None, None), &client)?;
+        h.send(ix::submit_deliverable(owner.pubkey(), agent, job, [1u8; 32], "ipfs://out".to_string()), &owner)?;
+        h.send(ix::complete_job(client.pubkey(), agent, job, None), &client)?;
+        h.send(ix::rate_job(client.pubkey(), agent, job, rating, "ok".to_string()), &client)?;
+    }
+    h.send(ix::create_job(client.pubkey(), agent, 2, "Task".to_string(), None, None), &client)?;
+
+    // Closing the first job drops its row.
+    h.send(ix::close_job(client.pubkey(), pda::job(&agent, 0).0, None), &client)?;
+
+    let check = |indexer: &Indexer| -> Result<()> {
+        let sql = format!("SELECT avg_rating FROM agents WHERE address = '{agent}'");
+        assert_eq!(query::<f64>(indexer, &sql)?, 4.0);
+        let sql = format!("SELECT open_jobs FROM agents WHERE address = '{agent}'");
+        assert_eq!(query::<i64>(indexer, &sql)?, 1);
+        assert_eq!(query::<i64>(indexer, "SELECT COUNT(*) FROM jobs")?, 2);
+        assert_eq!(query::<i64>(indexer, "SELECT COUNT(*) FROM jobs WHERE is_open = 1")?, 1);
+        assert_eq!(query::<String>(indexer, "SELECT status FROM jobs WHERE job_id = 1")?, "completed");
+        assert_eq!(query::<i64>(indexer, "SELECT COUNT(*) FROM reviews")?, 2);
+        assert_eq!(query::<i64>(indexer, "SELECT total_jobs FROM platform")?, 3);
+        let sql = "SELECT agent FROM agent_tags WHERE tag = 'writing'";
+        assert_eq!(query::<String>(indexer, sql)?, agent.to_string());
+        let sql = "SELECT endpoint_url FROM agent_metadata";
+        assert_eq!(query::<String>(indexer, sql)?, "https://sarah.example/webhook");
+        let sql = "SELECT agent_count FROM categories WHERE name = 'Email writing'";
+        assert_eq!(query::<i64>(indexer, sql)?, 1);
+        let sql = "SELECT agent FROM agent_categories WHERE category_id = 1";
+        assert_eq!(query::<String>(indexer, sql)?, agent.to_string());
+        Ok(())
+    };
+    check(&h.indexer)?;
+
+    let events: Vec<String> = h
+        .indexer
+        .connection()
+        .prepare("SELECT name FROM events ORDER BY slot, idx")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    assert_eq!(events.first().map(String::as_str), Some("PlatformInitialized"));
+    assert_eq!(events.iter().filter(|name| *name == "JobRated").count(), 2);
+    assert_eq!(events.last().map(String::as_str), Some("JobClosed"));
+
+    // A snapshot into a fresh database gives the same state.
+    let mut fresh = Indexer::open_in_memory()?;
+    fresh.backfill(h.snapshot(), h.slot)?;
+    check(&fresh)?;
+
+    // Reads older than what is stored are ignored.
+    let mut stale = h.account_data(&agent).unwrap();
+    stale[8..40].copy_from_slice(&[0u8; 32]); // owner
+    h.indexer.ingest_account(&agent, &stale, 0)?;
+    let sql = format!("SELECT owner FROM agents WHERE address = '{agent}'");
+    assert_eq!(query::<String>(&h.indexer, &sql)?, owner.pubkey().to_string());
+
+    Ok(())
+}
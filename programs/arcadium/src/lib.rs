@@ -1,148 +1,838 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_2022_extensions::transfer_fee::{
+    harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint,
+};
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
 
 declare_id!("GNZJTKQDSJdDLLxuNYKMwV4qHL8fdxsABzfqmMyzhEHJ");
 
+/// How long after completion an unrated job must stay open before it can be
+/// closed, so the client still has a chance to leave a review.
+pub const RATING_GRACE_PERIOD_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Delay before a queued platform fee increase takes effect.
+pub const FEE_CHANGE_DELAY_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Largest platform fee increase a single change may queue (2%).
+pub const MAX_FEE_INCREASE_BPS: u16 = 200;
+
+/// Limits on `AgentMetadata` fields.
+pub const MAX_ENDPOINT_URL_LEN: usize = 200;
+pub const MAX_DESCRIPTION_LEN: usize = 500;
+pub const MAX_TAGS: usize = 5;
+pub const MAX_TAG_LEN: usize = 32;
+pub const MAX_CATEGORIES: usize = 3;
+
 #[program]
 pub mod arcadium {
     use super::*;
 
     /// Initialize the platform (one-time setup)
-    pub fn initialize(ctx: Context<Initialize>, platform_fee_bps: u16) -> Result<()> {
+    ///
+    /// `review_window_secs` is how long a client has to accept or dispute a
+    /// delivery before anyone can `finalize_job` it.
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        platform_fee_bps: u16,
+        review_window_secs: i64,
+    ) -> Result<()> {
         require!(platform_fee_bps <= 10000, ErrorCode::InvalidFeeBps);
+        require!(review_window_secs > 0, ErrorCode::InvalidReviewWindow);
         
         let platform = &mut ctx.accounts.platform;
         platform.authority = ctx.accounts.authority.key();
+        platform.arbiter = ctx.accounts.authority.key();
+        platform.pending_authority = None;
         platform.platform_fee_bps = platform_fee_bps;
+        platform.pending_fee_bps = None;
+        platform.pending_fee_effective_at = 0;
+        platform.review_window_secs = review_window_secs;
         platform.total_jobs = 0;
         platform.total_volume = 0;
+        platform.fees_collected = 0;
+        platform.fees_withdrawn = 0;
+        platform.paused = false;
+        platform.treasury_bump = ctx.bumps.treasury;
+
+        emit!(PlatformInitialized {
+            authority: platform.authority,
+            platform_fee_bps,
+            review_window_secs,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         
         Ok(())
     }
 
+    /// Appoint the arbiter allowed to resolve disputes (authority only)
+    pub fn set_arbiter(ctx: Context<SetArbiter>, arbiter: Pubkey) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+        platform.arbiter = arbiter;
+
+        emit!(ArbiterSet {
+            arbiter,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Pause or resume the marketplace (authority only). While paused no new
+    /// agents or jobs can be created; everything that moves existing funds
+    /// (completion, refunds, cancellations, withdrawals) keeps working.
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+        platform.paused = paused;
+
+        emit!(PlatformPauseSet {
+            paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Create a category agents can list themselves under (authority only)
+    pub fn create_category(ctx: Context<CreateCategory>, category_id: u16, name: String) -> Result<()> {
+        require!(name.len() <= 50, ErrorCode::NameTooLong);
+
+        let category = &mut ctx.accounts.category;
+        category.category_id = category_id;
+        category.name = name;
+        category.is_active = true;
+        category.agent_count = 0;
+        category.bump = ctx.bumps.category;
+
+        emit!(CategoryUpdated {
+            category: category.key(),
+            category_id,
+            name: category.name.clone(),
+            is_active: true,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Rename or (de)activate a category (authority only). Inactive
+    /// categories take no new agents; agents already listed keep them.
+    pub fn update_category(
+        ctx: Context<UpdateCategory>,
+        name: Option<String>,
+        is_active: Option<bool>,
+    ) -> Result<()> {
+        let category = &mut ctx.accounts.category;
+
+        if let Some(name) = name {
+            require!(name.len() <= 50, ErrorCode::NameTooLong);
+            category.name = name;
+        }
+        if let Some(is_active) = is_active {
+            category.is_active = is_active;
+        }
+
+        emit!(CategoryUpdated {
+            category: category.key(),
+            category_id: category.category_id,
+            name: category.name.clone(),
+            is_active: category.is_active,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Offer platform authority to a new key (authority only). Takes effect
+    /// once `new_authority` calls `accept_authority`.
+    ///
+    /// Admin instructions only require the authority's signature, never its
+    /// lamports, so a multisig vault PDA can hold the role.
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+        platform.pending_authority = Some(new_authority);
+
+        emit!(AuthorityProposed {
+            authority: platform.authority,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Complete an authority handoff (signed by the proposed authority)
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+        require!(
+            platform.pending_authority == Some(ctx.accounts.new_authority.key()),
+            ErrorCode::NotPendingAuthority
+        );
+
+        let previous_authority = platform.authority;
+        platform.authority = ctx.accounts.new_authority.key();
+        platform.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            previous_authority,
+            new_authority: platform.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Update platform settings (authority only); `None` leaves a field unchanged
+    ///
+    /// Fee cuts apply immediately. Increases are capped at
+    /// `MAX_FEE_INCREASE_BPS` and queued for `FEE_CHANGE_DELAY_SECS`,
+    /// replacing any change already queued. Jobs keep the fee they were
+    /// created with either way.
+    pub fn update_platform_config(
+        ctx: Context<UpdatePlatformConfig>,
+        platform_fee_bps: Option<u16>,
+        review_window_secs: Option<i64>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let platform = &mut ctx.accounts.platform;
+        apply_pending_fee(platform, now);
+
+        if let Some(platform_fee_bps) = platform_fee_bps {
+            require!(platform_fee_bps <= 10000, ErrorCode::InvalidFeeBps);
+            if platform_fee_bps <= platform.platform_fee_bps {
+                platform.platform_fee_bps = platform_fee_bps;
+                platform.pending_fee_bps = None;
+            } else {
+                require!(
+                    platform_fee_bps - platform.platform_fee_bps <= MAX_FEE_INCREASE_BPS,
+                    ErrorCode::FeeIncreaseTooLarge
+                );
+                platform.pending_fee_bps = Some(platform_fee_bps);
+                platform.pending_fee_effective_at = now + FEE_CHANGE_DELAY_SECS;
+            }
+        }
+        if let Some(review_window_secs) = review_window_secs {
+            require!(review_window_secs > 0, ErrorCode::InvalidReviewWindow);
+            platform.review_window_secs = review_window_secs;
+        }
+
+        emit!(PlatformConfigUpdated {
+            platform_fee_bps: platform.platform_fee_bps,
+            pending_fee_bps: platform.pending_fee_bps,
+            pending_fee_effective_at: platform.pending_fee_effective_at,
+            review_window_secs: platform.review_window_secs,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
     /// Register an agent
+    ///
+    /// Passing a `mint` prices the agent in that SPL token instead of SOL;
+    /// `price_lamports` is then denominated in the mint's base units.
+    ///
+    /// The `Category` accounts for `metadata.categories` go in remaining
+    /// accounts, writable, in any order. They come after all six optional
+    /// token accounts, with the program ID in place of unused ones.
     pub fn register_agent(
         ctx: Context<RegisterAgent>,
         name: String,
         skill_uri: String,
         price_lamports: u64,
+        metadata: AgentMetadataInput,
     ) -> Result<()> {
         require!(name.len() <= 50, ErrorCode::NameTooLong);
         require!(skill_uri.len() <= 200, ErrorCode::UriTooLong);
         require!(price_lamports > 0, ErrorCode::InvalidPrice);
+        validate_metadata(&metadata)?;
+        require!(!ctx.accounts.platform.paused, ErrorCode::PlatformPaused);
+
+        let payment_mint = ctx.accounts.mint.as_ref().map(|mint| mint.key());
+        if let Some(mint) = &ctx.accounts.mint {
+            check_mint_extensions(mint)?;
+            require!(
+                ctx.accounts.agent_vault_token.is_some() && ctx.accounts.treasury_token.is_some(),
+                ErrorCode::MissingTokenAccount
+            );
+        }
+
+        let owner_registry = &mut ctx.accounts.owner_registry;
+        if owner_registry.owner == Pubkey::default() {
+            owner_registry.owner = ctx.accounts.owner.key();
+            owner_registry.bump = ctx.bumps.owner_registry;
+        }
+        let agent_id = owner_registry.agents_created;
+        owner_registry.agents_created += 1;
+        owner_registry.agent_count += 1;
 
         let agent = &mut ctx.accounts.agent;
         agent.owner = ctx.accounts.owner.key();
+        agent.creator = ctx.accounts.owner.key();
+        agent.pending_owner = None;
+        agent.operator = None;
+        agent.agent_id = agent_id;
         agent.name = name;
         agent.skill_uri = skill_uri;
         agent.price_lamports = price_lamports;
+        agent.payment_mint = payment_mint;
+        agent.jobs_created = 0;
+        agent.open_jobs = 0;
         agent.jobs_completed = 0;
         agent.total_earned = 0;
         agent.rating_sum = 0;
         agent.rating_count = 0;
+        agent.deadlines_missed = 0;
+        agent.disputes_resolved = 0;
+        agent.dispute_share_bps_sum = 0;
         agent.is_active = true;
         agent.bump = ctx.bumps.agent;
 
+        let agent_metadata = &mut ctx.accounts.agent_metadata;
+        agent_metadata.agent = agent.key();
+        agent_metadata.bump = ctx.bumps.agent_metadata;
+        agent_metadata.set(metadata);
+        recount_categories(ctx.remaining_accounts, &[], &agent_metadata.categories)?;
+
+        emit!(AgentRegistered {
+            agent: agent.key(),
+            owner: agent.owner,
+            agent_id,
+            price_lamports,
+            payment_mint,
+            categories: agent_metadata.categories.clone(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Update an agent's listing (owner only); `None` leaves a field unchanged
+    ///
+    /// Existing jobs keep the price snapshotted in `Job.price_lamports`.
+    pub fn update_agent(
+        ctx: Context<UpdateAgent>,
+        name: Option<String>,
+        skill_uri: Option<String>,
+        price_lamports: Option<u64>,
+        is_active: Option<bool>,
+    ) -> Result<()> {
+        let agent = &mut ctx.accounts.agent;
+
+        if let Some(name) = name {
+            require!(name.len() <= 50, ErrorCode::NameTooLong);
+            agent.name = name;
+        }
+        if let Some(skill_uri) = skill_uri {
+            require!(skill_uri.len() <= 200, ErrorCode::UriTooLong);
+            agent.skill_uri = skill_uri;
+        }
+        if let Some(price_lamports) = price_lamports {
+            require!(price_lamports > 0, ErrorCode::InvalidPrice);
+            agent.price_lamports = price_lamports;
+        }
+        if let Some(is_active) = is_active {
+            agent.is_active = is_active;
+        }
+
+        emit!(AgentUpdated {
+            agent: agent.key(),
+            price_lamports: agent.price_lamports,
+            is_active: agent.is_active,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Replace an agent's metadata (owner only). The account is resized to fit,
+    /// with the owner paying for growth and refunded on shrink.
+    ///
+    /// Remaining accounts: the `Category` accounts for every category being
+    /// added or dropped, writable, in any order.
+    pub fn update_agent_metadata(
+        ctx: Context<UpdateAgentMetadata>,
+        metadata: AgentMetadataInput,
+    ) -> Result<()> {
+        validate_metadata(&metadata)?;
+        resize_account(
+            &ctx.accounts.agent_metadata.to_account_info(),
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+            AgentMetadata::space(&metadata),
+        )?;
+
+        let agent_metadata = &mut ctx.accounts.agent_metadata;
+        let previous_categories = std::mem::take(&mut agent_metadata.categories);
+        agent_metadata.set(metadata);
+        recount_categories(ctx.remaining_accounts, &previous_categories, &agent_metadata.categories)?;
+
+        emit!(AgentMetadataUpdated {
+            agent: ctx.accounts.agent.key(),
+            endpoint_url: agent_metadata.endpoint_url.clone(),
+            tags: agent_metadata.tags.clone(),
+            categories: agent_metadata.categories.clone(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set or rotate the agent's operator hot key (owner only); `None` revokes
+    /// it. The operator can run the job lifecycle but never moves funds or
+    /// edits the listing.
+    pub fn set_agent_operator(ctx: Context<SetAgentOperator>, operator: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.agent.operator = operator;
+
+        emit!(AgentOperatorSet {
+            agent: ctx.accounts.agent.key(),
+            operator,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Offer the agent (and its reputation and vault) to a new owner wallet.
+    /// Takes effect once `new_owner` calls `accept_agent_owner`; a later
+    /// proposal replaces an earlier one.
+    pub fn propose_agent_owner(ctx: Context<ProposeAgentOwner>, new_owner: Pubkey) -> Result<()> {
+        let agent = &mut ctx.accounts.agent;
+        agent.pending_owner = Some(new_owner);
+
+        emit!(AgentOwnerProposed {
+            agent: agent.key(),
+            owner: agent.owner,
+            pending_owner: new_owner,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Complete an ownership handoff (signed by the proposed owner)
+    pub fn accept_agent_owner(ctx: Context<AcceptAgentOwner>) -> Result<()> {
+        let agent = &mut ctx.accounts.agent;
+        require!(
+            agent.pending_owner == Some(ctx.accounts.new_owner.key()),
+            ErrorCode::NotPendingOwner
+        );
+
+        let previous_owner = agent.owner;
+        agent.owner = ctx.accounts.new_owner.key();
+        agent.pending_owner = None;
+        // The previous owner's hot key doesn't carry over
+        agent.operator = None;
+
+        emit!(AgentOwnerTransferred {
+            agent: agent.key(),
+            previous_owner,
+            new_owner: agent.owner,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(())
     }
 
     /// Create a job (client pays, funds go to escrow)
+    ///
+    /// `deadline` is an optional unix timestamp after which anyone may call
+    /// `expire_job` to refund the client if the job is still open.
     pub fn create_job(
         ctx: Context<CreateJob>,
         task_description: String,
+        deadline: Option<i64>,
     ) -> Result<()> {
         require!(task_description.len() <= 500, ErrorCode::TaskTooLong);
 
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(deadline) = deadline {
+            require!(deadline > now, ErrorCode::InvalidDeadline);
+        }
+
         let agent = &ctx.accounts.agent;
         require!(agent.is_active, ErrorCode::AgentNotActive);
 
+        let platform = &mut ctx.accounts.platform;
+        require!(!platform.paused, ErrorCode::PlatformPaused);
+        apply_pending_fee(platform, now);
+
         let job = &mut ctx.accounts.job;
         job.agent = agent.key();
+        job.job_id = agent.jobs_created;
         job.client = ctx.accounts.client.key();
         job.task_description = task_description;
         job.price_lamports = agent.price_lamports;
+        job.mint = agent.payment_mint;
+        job.platform_fee_bps = platform.platform_fee_bps;
         job.status = JobStatus::Created;
-        job.created_at = Clock::get()?.unix_timestamp;
+        job.created_at = now;
+        job.deadline = deadline;
         job.bump = ctx.bumps.job;
         job.escrow_bump = ctx.bumps.escrow;
 
-        // Transfer payment from client to escrow PDA
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.client.to_account_info(),
-                to: ctx.accounts.escrow.to_account_info(),
-            },
-        );
-        transfer(cpi_context, agent.price_lamports)?;
+        // Transfer payment from client to escrow PDA (or its token account),
+        // recording what actually arrived after any transfer fee
+        let escrowed_amount = match agent.payment_mint {
+            None => {
+                let cpi_context = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.client.to_account_info(),
+                        to: ctx.accounts.escrow.to_account_info(),
+                    },
+                );
+                transfer(cpi_context, agent.price_lamports)?;
+                agent.price_lamports
+            }
+            Some(_) => {
+                let mint = required(&ctx.accounts.mint)?;
+                check_mint_extensions(mint)?;
+                // The ATA may already exist with tokens in it; only count ours
+                let balance_before = required(&ctx.accounts.escrow_token)?.amount;
+                let cpi_context = CpiContext::new(
+                    required(&ctx.accounts.token_program)?.to_account_info(),
+                    TransferChecked {
+                        from: required(&ctx.accounts.client_token)?.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: required(&ctx.accounts.escrow_token)?.to_account_info(),
+                        authority: ctx.accounts.client.to_account_info(),
+                    },
+                );
+                transfer_checked(cpi_context, agent.price_lamports, mint.decimals)?;
+
+                let escrow_token = ctx
+                    .accounts
+                    .escrow_token
+                    .as_mut()
+                    .ok_or(ErrorCode::MissingTokenAccount)?;
+                escrow_token.reload()?;
+                escrow_token.amount - balance_before
+            }
+        };
+        ctx.accounts.job.escrowed_amount = escrowed_amount;
+
+        // Bump the agent's job counter so the next job gets a fresh PDA
+        let agent_mut = &mut ctx.accounts.agent;
+        agent_mut.jobs_created += 1;
+        agent_mut.open_jobs += 1;
 
         // Increment platform job counter
         let platform = &mut ctx.accounts.platform;
         platform.total_jobs += 1;
 
+        let job = &ctx.accounts.job;
+        emit!(JobCreated {
+            job: job.key(),
+            agent: job.agent,
+            client: job.client,
+            job_id: job.job_id,
+            price_lamports: job.price_lamports,
+            escrowed_amount,
+            mint: job.mint,
+            platform_fee_bps: job.platform_fee_bps,
+            deadline: job.deadline,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Record the agent's deliverable (content hash + URI) for client review.
+    /// Signed by the agent's owner or its operator.
+    pub fn submit_deliverable(
+        ctx: Context<SubmitDeliverable>,
+        content_hash: [u8; 32],
+        uri: String,
+    ) -> Result<()> {
+        require!(uri.len() <= 200, ErrorCode::UriTooLong);
+
+        let agent = &ctx.accounts.agent;
+        let signer = ctx.accounts.signer.key();
+        require!(
+            signer == agent.owner || agent.operator == Some(signer),
+            ErrorCode::NotOwnerOrOperator
+        );
+
+        let job = &mut ctx.accounts.job;
+        require!(job.status == JobStatus::Created, ErrorCode::InvalidJobStatus);
+
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(deadline) = job.deadline {
+            require!(now <= deadline, ErrorCode::DeadlinePassed);
+        }
+
+        job.deliverable_hash = Some(content_hash);
+        job.deliverable_uri = uri;
+        job.delivered_at = Some(now);
+        job.review_ends_at = Some(now + ctx.accounts.platform.review_window_secs);
+        job.status = JobStatus::Delivered;
+
+        emit!(DeliverableSubmitted {
+            job: job.key(),
+            agent: job.agent,
+            content_hash,
+            review_ends_at: now + ctx.accounts.platform.review_window_secs,
+            timestamp: now,
+        });
+
         Ok(())
     }
 
-    /// Complete a job (agent delivers work, client accepts, payment releases)
+    /// Complete a job (client accepts the delivered work, payment releases)
     pub fn complete_job(ctx: Context<CompleteJob>) -> Result<()> {
+        let job = &ctx.accounts.job;
+        require!(job.status == JobStatus::Delivered, ErrorCode::InvalidJobStatus);
+
+        let escrow = JobEscrow::new(
+            job,
+            &ctx.accounts.escrow,
+            &ctx.accounts.system_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.escrow_token,
+            &ctx.accounts.token_program,
+        )?;
+        settle_job(
+            &mut ctx.accounts.job,
+            &mut ctx.accounts.agent,
+            &mut ctx.accounts.platform,
+            &escrow,
+            &ctx.accounts.agent_vault,
+            &ctx.accounts.agent_vault_token,
+            &ctx.accounts.treasury,
+            &ctx.accounts.treasury_token,
+        )
+    }
+
+    /// Release payment for a delivered job once the client's review window
+    /// has closed without a dispute (permissionless)
+    pub fn finalize_job(ctx: Context<FinalizeJob>) -> Result<()> {
+        let job = &ctx.accounts.job;
+        require!(job.status == JobStatus::Delivered, ErrorCode::InvalidJobStatus);
+
+        let review_ends_at = job.review_ends_at.ok_or(ErrorCode::InvalidJobStatus)?;
+        require!(
+            Clock::get()?.unix_timestamp > review_ends_at,
+            ErrorCode::ReviewWindowOpen
+        );
+
+        let escrow = JobEscrow::new(
+            job,
+            &ctx.accounts.escrow,
+            &ctx.accounts.system_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.escrow_token,
+            &ctx.accounts.token_program,
+        )?;
+        settle_job(
+            &mut ctx.accounts.job,
+            &mut ctx.accounts.agent,
+            &mut ctx.accounts.platform,
+            &escrow,
+            &ctx.accounts.agent_vault,
+            &ctx.accounts.agent_vault_token,
+            &ctx.accounts.treasury,
+            &ctx.accounts.treasury_token,
+        )
+    }
+
+    /// Cancel a job before the agent has delivered (client gets a full refund)
+    pub fn cancel_job(ctx: Context<CancelJob>) -> Result<()> {
+        let job = &ctx.accounts.job;
+        require!(job.status == JobStatus::Created, ErrorCode::InvalidJobStatus);
+
+        // Refund the entire escrow balance (price + rent) to the client
+        let escrow = JobEscrow::new(
+            job,
+            &ctx.accounts.escrow,
+            &ctx.accounts.system_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.escrow_token,
+            &ctx.accounts.token_program,
+        )?;
+        let refunded_amount = escrow.balance();
+        escrow.pay_and_close(
+            &ctx.accounts.client.to_account_info(),
+            &ctx.accounts.client_token,
+            refunded_amount,
+        )?;
+
         let job = &mut ctx.accounts.job;
+        job.status = JobStatus::Cancelled;
+
+        let agent = &mut ctx.accounts.agent;
+        agent.open_jobs -= 1;
+
+        emit!(JobCancelled {
+            job: job.key(),
+            agent: job.agent,
+            client: job.client,
+            refunded_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Refund a job whose deadline passed before delivery (permissionless)
+    pub fn expire_job(ctx: Context<ExpireJob>) -> Result<()> {
+        let job = &ctx.accounts.job;
         require!(job.status == JobStatus::Created, ErrorCode::InvalidJobStatus);
 
-        let _agent = &ctx.accounts.agent;
+        let deadline = job.deadline.ok_or(ErrorCode::NoDeadline)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now > deadline, ErrorCode::DeadlineNotReached);
+
+        // Refund the entire escrow balance (price + rent) to the client
+        let escrow = JobEscrow::new(
+            job,
+            &ctx.accounts.escrow,
+            &ctx.accounts.system_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.escrow_token,
+            &ctx.accounts.token_program,
+        )?;
+        let refunded_amount = escrow.balance();
+        escrow.pay_and_close(
+            &ctx.accounts.client.to_account_info(),
+            &ctx.accounts.client_token,
+            refunded_amount,
+        )?;
+
+        let job = &mut ctx.accounts.job;
+        job.status = JobStatus::Refunded;
+        job.completed_at = Some(now);
+
+        // Count the missed deadline against the agent
+        let agent = &mut ctx.accounts.agent;
+        agent.deadlines_missed += 1;
+        agent.open_jobs -= 1;
+
+        emit!(JobExpired {
+            job: job.key(),
+            agent: job.agent,
+            client: job.client,
+            refunded_amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Freeze a job's escrow pending arbitration (client only)
+    pub fn dispute_job(ctx: Context<DisputeJob>) -> Result<()> {
+        let job = &mut ctx.accounts.job;
+        require!(
+            job.status == JobStatus::Created || job.status == JobStatus::Delivered,
+            ErrorCode::InvalidJobStatus
+        );
+
+        // Deliveries can only be disputed within the review window
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(review_ends_at) = job.review_ends_at {
+            require!(now <= review_ends_at, ErrorCode::ReviewWindowClosed);
+        }
+
+        job.status = JobStatus::Disputed;
+
+        emit!(JobDisputed {
+            job: job.key(),
+            agent: job.agent,
+            client: job.client,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Settle a disputed job, paying `agent_share_bps` of the price to the
+    /// agent (minus platform fee) and refunding the rest to the client
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, agent_share_bps: u16) -> Result<()> {
+        require!(agent_share_bps <= 10000, ErrorCode::InvalidShareBps);
+
+        let job = &ctx.accounts.job;
+        require!(job.status == JobStatus::Disputed, ErrorCode::InvalidJobStatus);
+
         let platform = &ctx.accounts.platform;
+        let arbiter = ctx.accounts.arbiter.key();
+        require!(
+            arbiter == platform.authority || arbiter == platform.arbiter,
+            ErrorCode::NotArbiter
+        );
 
-        // Calculate splits: 90% to agent, 10% to platform
-        let total_amount = job.price_lamports;
-        let platform_fee = (total_amount as u128)
-            .checked_mul(platform.platform_fee_bps as u128)
+        // Agent's gross share of the escrowed amount, platform fee taken from it
+        let agent_gross = (job.escrowed_amount as u128)
+            .checked_mul(agent_share_bps as u128)
             .unwrap()
             .checked_div(10000)
             .unwrap() as u64;
-        let agent_amount = total_amount.checked_sub(platform_fee).unwrap();
-
-        // Transfer from escrow to agent vault
-        // (bind pubkeys so the references outlive this statement)
-        let job_key = job.key();
-        let escrow_seeds = &[
-            b"escrow",
-            job_key.as_ref(),
-            &[job.escrow_bump],
-        ];
-        let signer_seeds = &[&escrow_seeds[..]];
+        let platform_fee = platform_fee(agent_gross, job.platform_fee_bps);
+        let agent_amount = agent_gross.checked_sub(platform_fee).unwrap();
 
-        let cpi_context = CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.escrow.to_account_info(),
-                to: ctx.accounts.agent_vault.to_account_info(),
-            },
-            signer_seeds,
-        );
-        transfer(cpi_context, agent_amount)?;
+        let escrow = JobEscrow::new(
+            job,
+            &ctx.accounts.escrow,
+            &ctx.accounts.system_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.escrow_token,
+            &ctx.accounts.token_program,
+        )?;
+        // Client gets everything else (refunded share + escrow rent)
+        let client_amount = escrow.balance().checked_sub(agent_gross).unwrap();
 
-        // Transfer platform fee
-        let cpi_context_fee = CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.escrow.to_account_info(),
-                to: ctx.accounts.platform_authority.to_account_info(),
-            },
-            signer_seeds,
-        );
-        transfer(cpi_context_fee, platform_fee)?;
+        escrow.pay(
+            &ctx.accounts.agent_vault.to_account_info(),
+            &ctx.accounts.agent_vault_token,
+            agent_amount,
+        )?;
+        escrow.pay(
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.treasury_token,
+            platform_fee,
+        )?;
+        escrow.pay_and_close(
+            &ctx.accounts.client.to_account_info(),
+            &ctx.accounts.client_token,
+            client_amount,
+        )?;
 
-        // Update job status
-        job.status = JobStatus::Completed;
-        job.completed_at = Some(Clock::get()?.unix_timestamp);
+        let now = Clock::get()?.unix_timestamp;
+        let job = &mut ctx.accounts.job;
+        job.status = JobStatus::Resolved;
+        job.completed_at = Some(now);
 
-        // Update agent stats
-        let agent_mut = &mut ctx.accounts.agent;
-        agent_mut.jobs_completed += 1;
-        agent_mut.total_earned += agent_amount;
+        // Record the outcome (average share = dispute_share_bps_sum / disputes_resolved)
+        let agent = &mut ctx.accounts.agent;
+        agent.total_earned += agent_amount;
+        agent.disputes_resolved += 1;
+        agent.dispute_share_bps_sum += agent_share_bps as u64;
+        agent.open_jobs -= 1;
 
-        // Update platform stats
         let platform_mut = &mut ctx.accounts.platform;
-        platform_mut.total_volume += total_amount;
+        if job.mint.is_none() {
+            platform_mut.total_volume += agent_gross;
+            platform_mut.fees_collected += platform_fee;
+        }
+
+        emit!(DisputeResolved {
+            job: job.key(),
+            agent: job.agent,
+            client: job.client,
+            agent_share_bps,
+            agent_amount,
+            platform_fee,
+            client_amount,
+            mint: job.mint,
+            timestamp: now,
+        });
 
         Ok(())
     }
@@ -162,21 +852,75 @@ pub mod arcadium {
         review.rating = rating;
         review.comment = comment;
         review.created_at = Clock::get()?.unix_timestamp;
+        let created_at = review.created_at;
+
+        let job = &mut ctx.accounts.job;
+        job.rated = true;
 
         // Update agent rating
         let agent = &mut ctx.accounts.agent;
         agent.rating_sum += rating as u64;
         agent.rating_count += 1;
 
+        emit!(JobRated {
+            job: job.key(),
+            agent: job.agent,
+            client: job.client,
+            rating,
+            timestamp: created_at,
+        });
+
+        Ok(())
+    }
+
+    /// Close a finished job and its leftover escrow, refunding rent to the
+    /// client. Completed jobs must be rated first or be past the grace period.
+    pub fn close_job(ctx: Context<CloseJob>) -> Result<()> {
+        let job = &ctx.accounts.job;
+        match job.status {
+            JobStatus::Completed => {
+                if !job.rated {
+                    let completed_at = job.completed_at.ok_or(ErrorCode::InvalidJobStatus)?;
+                    require!(
+                        Clock::get()?.unix_timestamp >= completed_at + RATING_GRACE_PERIOD_SECS,
+                        ErrorCode::RatingGracePeriod
+                    );
+                }
+
+                // Payouts leave the escrow's rent (and a token account) behind
+                let escrow = JobEscrow::new(
+                    job,
+                    &ctx.accounts.escrow,
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.mint,
+                    &ctx.accounts.escrow_token,
+                    &ctx.accounts.token_program,
+                )?;
+                // Tokens sent to the escrow's ATA outside `create_job` go to the client
+                let client = ctx.accounts.client.to_account_info();
+                if job.mint.is_some() && escrow.balance() > 0 {
+                    escrow.pay(&client, &ctx.accounts.client_token, escrow.balance())?;
+                }
+                escrow.close(&client)?;
+            }
+            // These paths already closed the escrow out to the client
+            JobStatus::Cancelled | JobStatus::Refunded | JobStatus::Resolved => {}
+            _ => return err!(ErrorCode::InvalidJobStatus),
+        }
+
+        emit!(JobClosed {
+            job: job.key(),
+            agent: job.agent,
+            client: job.client,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(())
     }
 
     /// Withdraw earnings from agent vault
     pub fn withdraw_earnings(ctx: Context<WithdrawEarnings>, amount: u64) -> Result<()> {
         let agent = &ctx.accounts.agent;
-        let vault_balance = ctx.accounts.agent_vault.lamports();
-        
-        require!(amount <= vault_balance, ErrorCode::InsufficientFunds);
 
         // (bind pubkey so the reference outlives this statement)
         let agent_key = agent.key();
@@ -187,51 +931,733 @@ pub mod arcadium {
         ];
         let signer_seeds = &[&vault_seeds[..]];
 
-        let cpi_context = CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.agent_vault.to_account_info(),
-                to: ctx.accounts.owner.to_account_info(),
-            },
-            signer_seeds,
-        );
-        transfer(cpi_context, amount)?;
+        match agent.payment_mint {
+            None => {
+                let vault_balance = ctx.accounts.agent_vault.lamports();
+                require!(amount <= vault_balance, ErrorCode::InsufficientFunds);
+
+                let cpi_context = CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.agent_vault.to_account_info(),
+                        to: ctx.accounts.owner.to_account_info(),
+                    },
+                    signer_seeds,
+                );
+                transfer(cpi_context, amount)?;
+            }
+            Some(_) => {
+                let mint = required(&ctx.accounts.mint)?;
+                let vault_token = required(&ctx.accounts.agent_vault_token)?;
+                require!(amount <= vault_token.amount, ErrorCode::InsufficientFunds);
+
+                let cpi_context = CpiContext::new_with_signer(
+                    required(&ctx.accounts.token_program)?.to_account_info(),
+                    TransferChecked {
+                        from: vault_token.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: required(&ctx.accounts.owner_token)?.to_account_info(),
+                        authority: ctx.accounts.agent_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                );
+                transfer_checked(cpi_context, amount, mint.decimals)?;
+            }
+        }
+
+        emit!(EarningsWithdrawn {
+            agent: agent_key,
+            owner: ctx.accounts.owner.key(),
+            amount,
+            mint: agent.payment_mint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw accrued platform fees from the treasury to `recipient`
+    /// (authority only). Withdraws from the treasury's token account when a
+    /// `mint` is passed, lamports otherwise.
+    pub fn withdraw_platform_fees(ctx: Context<WithdrawPlatformFees>, amount: u64) -> Result<()> {
+        let treasury_seeds = &[b"treasury".as_ref(), &[ctx.accounts.platform.treasury_bump]];
+        let signer_seeds = &[&treasury_seeds[..]];
+
+        match &ctx.accounts.mint {
+            None => {
+                // The treasury must stay rent-exempt to keep receiving fees
+                let available = ctx
+                    .accounts
+                    .treasury
+                    .lamports()
+                    .saturating_sub(Rent::get()?.minimum_balance(0));
+                require!(amount <= available, ErrorCode::InsufficientFunds);
+
+                let cpi_context = CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.treasury.to_account_info(),
+                        to: ctx.accounts.recipient.to_account_info(),
+                    },
+                    signer_seeds,
+                );
+                transfer(cpi_context, amount)?;
+
+                let platform = &mut ctx.accounts.platform;
+                platform.fees_withdrawn += amount;
+            }
+            Some(mint) => {
+                let treasury_token = required(&ctx.accounts.treasury_token)?;
+                require!(amount <= treasury_token.amount, ErrorCode::InsufficientFunds);
+
+                let cpi_context = CpiContext::new_with_signer(
+                    required(&ctx.accounts.token_program)?.to_account_info(),
+                    TransferChecked {
+                        from: treasury_token.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: required(&ctx.accounts.recipient_token)?.to_account_info(),
+                        authority: ctx.accounts.treasury.to_account_info(),
+                    },
+                    signer_seeds,
+                );
+                transfer_checked(cpi_context, amount, mint.decimals)?;
+            }
+        }
+
+        emit!(PlatformFeesWithdrawn {
+            recipient: ctx.accounts.recipient.key(),
+            amount,
+            mint: ctx.accounts.mint.as_ref().map(|mint| mint.key()),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Retire an agent (owner only): sweep the vault to the owner and close
+    /// the agent account for its rent. Refused while any job is still open.
+    ///
+    /// Remaining accounts: the `Category` accounts the agent is listed under,
+    /// after all four optional token accounts (the program ID in place of
+    /// unused ones).
+    pub fn close_agent(ctx: Context<CloseAgent>) -> Result<()> {
+        let agent = &ctx.accounts.agent;
+        require!(agent.open_jobs == 0, ErrorCode::AgentHasOpenJobs);
+
+        // (bind pubkey so the reference outlives this statement)
+        let agent_key = agent.key();
+        let vault_seeds = &[
+            b"agent_vault",
+            agent_key.as_ref(),
+            &[ctx.bumps.agent_vault],
+        ];
+        let signer_seeds = &[&vault_seeds[..]];
+
+        // Sweep token earnings and close the vault's token account
+        if agent.payment_mint.is_some() {
+            let mint = required(&ctx.accounts.mint)?;
+            let vault_token = required(&ctx.accounts.agent_vault_token)?;
+            let token_program = required(&ctx.accounts.token_program)?;
+
+            let cpi_context = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: vault_token.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: required(&ctx.accounts.owner_token)?.to_account_info(),
+                    authority: ctx.accounts.agent_vault.to_account_info(),
+                },
+                signer_seeds,
+            );
+            transfer_checked(cpi_context, vault_token.amount, mint.decimals)?;
+
+            harvest_withheld_fees(mint, vault_token, token_program)?;
+            let cpi_context = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                CloseAccount {
+                    account: vault_token.to_account_info(),
+                    destination: ctx.accounts.owner.to_account_info(),
+                    authority: ctx.accounts.agent_vault.to_account_info(),
+                },
+                signer_seeds,
+            );
+            close_account(cpi_context)?;
+        }
+
+        // Sweep the vault's lamports (earnings + rent) to the owner
+        let vault_balance = ctx.accounts.agent_vault.lamports();
+        if vault_balance > 0 {
+            let cpi_context = CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.agent_vault.to_account_info(),
+                    to: ctx.accounts.owner.to_account_info(),
+                },
+                signer_seeds,
+            );
+            transfer(cpi_context, vault_balance)?;
+        }
+
+        let owner_registry = &mut ctx.accounts.owner_registry;
+        owner_registry.agent_count = owner_registry
+            .agent_count
+            .checked_sub(1)
+            .ok_or(ErrorCode::AgentCountUnderflow)?;
+        recount_categories(ctx.remaining_accounts, &ctx.accounts.agent_metadata.categories, &[])?;
+
+        emit!(AgentClosed {
+            agent: agent_key,
+            owner: ctx.accounts.owner.key(),
+            categories: ctx.accounts.agent_metadata.categories.clone(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
 }
 
 // ============================================================================
-// Contexts
+// Helpers
 // ============================================================================
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + Platform::INIT_SPACE,
-        seeds = [b"platform"],
-        bump
-    )]
-    pub platform: Account<'info, Platform>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
+fn validate_metadata(metadata: &AgentMetadataInput) -> Result<()> {
+    let url = &metadata.endpoint_url;
+    require!(url.len() <= MAX_ENDPOINT_URL_LEN, ErrorCode::UriTooLong);
+    require!(
+        url.is_empty() || url.starts_with("https://"),
+        ErrorCode::InvalidEndpoint
+    );
+    require!(metadata.description.len() <= MAX_DESCRIPTION_LEN, ErrorCode::DescriptionTooLong);
+    require!(metadata.tags.len() <= MAX_TAGS, ErrorCode::TooManyTags);
+    for tag in &metadata.tags {
+        require!(!tag.is_empty() && tag.len() <= MAX_TAG_LEN, ErrorCode::InvalidTag);
+    }
+    require!(metadata.categories.len() <= MAX_CATEGORIES, ErrorCode::TooManyCategories);
+    for (i, category_id) in metadata.categories.iter().enumerate() {
+        require!(!metadata.categories[..i].contains(category_id), ErrorCode::DuplicateCategory);
+    }
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct RegisterAgent<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + Agent::INIT_SPACE,
-        seeds = [b"agent", owner.key().as_ref()],
-        bump
-    )]
-    pub agent: Account<'info, Agent>,
+/// Keep `Category.agent_count` in step with an agent moving from `previous`
+/// to `current` categories. `categories` are the instruction's remaining
+/// accounts; each category joined or left must be among them.
+fn recount_categories(categories: &[AccountInfo], previous: &[u16], current: &[u16]) -> Result<()> {
+    for &category_id in current.iter().filter(|id| !previous.contains(id)) {
+        adjust_category(categories, category_id, true)?;
+    }
+    for &category_id in previous.iter().filter(|id| !current.contains(id)) {
+        adjust_category(categories, category_id, false)?;
+    }
+    Ok(())
+}
+
+fn adjust_category(categories: &[AccountInfo], category_id: u16, joined: bool) -> Result<()> {
+    for info in categories {
+        // Only this program can create accounts with the `Category`
+        // discriminator, and only at the PDA for their `category_id`.
+        if info.owner != &crate::ID || !info.is_writable {
+            continue;
+        }
+        let mut data = info.try_borrow_mut_data()?;
+        let Ok(mut category) = Category::try_deserialize(&mut &data[..]) else {
+            continue;
+        };
+        if category.category_id != category_id {
+            continue;
+        }
+        if joined {
+            require!(category.is_active, ErrorCode::CategoryInactive);
+            category.agent_count += 1;
+        } else {
+            category.agent_count = category
+                .agent_count
+                .checked_sub(1)
+                .ok_or(ErrorCode::AgentCountUnderflow)?;
+        }
+        category.try_serialize(&mut &mut data[..])?;
+        return Ok(());
+    }
+    err!(ErrorCode::MissingCategoryAccount)
+}
+
+/// Release a delivered job's escrow: agent share to the vault, platform fee
+/// to the treasury, then mark the job completed and update stats.
+#[allow(clippy::too_many_arguments)]
+fn settle_job<'info>(
+    job: &mut Account<'info, Job>,
+    agent: &mut Account<'info, Agent>,
+    platform: &mut Account<'info, Platform>,
+    escrow: &JobEscrow<'_, 'info>,
+    agent_vault: &SystemAccount<'info>,
+    agent_vault_token: &Option<InterfaceAccount<'info, TokenAccount>>,
+    treasury: &SystemAccount<'info>,
+    treasury_token: &Option<InterfaceAccount<'info, TokenAccount>>,
+) -> Result<()> {
+    // Calculate splits: 90% to agent, 10% to platform, based on what the
+    // escrow actually received (token transfer fees may have been withheld)
+    let total_amount = job.escrowed_amount;
+    let platform_fee = platform_fee(total_amount, job.platform_fee_bps);
+    let agent_amount = total_amount.checked_sub(platform_fee).unwrap();
+
+    // Transfer from escrow to agent vault, then the platform fee to the treasury
+    escrow.pay(&agent_vault.to_account_info(), agent_vault_token, agent_amount)?;
+    escrow.pay(&treasury.to_account_info(), treasury_token, platform_fee)?;
+
+    // Update job status
+    let now = Clock::get()?.unix_timestamp;
+    job.status = JobStatus::Completed;
+    job.completed_at = Some(now);
+
+    // Update agent stats
+    agent.open_jobs -= 1;
+    agent.jobs_completed += 1;
+    agent.total_earned += agent_amount;
+
+    // Update platform stats
+    if job.mint.is_none() {
+        platform.total_volume += total_amount;
+        platform.fees_collected += platform_fee;
+    }
+
+    emit!(JobCompleted {
+        job: job.key(),
+        agent: job.agent,
+        client: job.client,
+        agent_amount,
+        platform_fee,
+        mint: job.mint,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Promote a queued fee change once its effective time has passed.
+fn apply_pending_fee(platform: &mut Platform, now: i64) {
+    if let Some(pending_fee_bps) = platform.pending_fee_bps {
+        if now >= platform.pending_fee_effective_at {
+            platform.platform_fee_bps = pending_fee_bps;
+            platform.pending_fee_bps = None;
+        }
+    }
+}
+
+/// Platform cut of `amount` at `fee_bps` basis points (rounded down).
+fn platform_fee(amount: u64, fee_bps: u16) -> u64 {
+    (amount as u128)
+        .checked_mul(fee_bps as u128)
+        .unwrap()
+        .checked_div(10000)
+        .unwrap() as u64
+}
+
+/// Reject Token-2022 mints whose extensions make escrow unsafe or unusable:
+/// non-transferable tokens can't leave the escrow, a permanent delegate can
+/// drain it, transfer hooks need extra accounts we don't pass, and a default
+/// frozen account state would lock the escrow's token account. Transfer-fee
+/// and interest-bearing mints are fine since payouts use the escrowed amount.
+fn check_mint_extensions(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != anchor_spl::token_2022::ID {
+        return Ok(());
+    }
+
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    for extension in state.get_extension_types()? {
+        require!(
+            !matches!(
+                extension,
+                ExtensionType::NonTransferable
+                    | ExtensionType::PermanentDelegate
+                    | ExtensionType::TransferHook
+                    | ExtensionType::DefaultAccountState
+            ),
+            ErrorCode::UnsupportedMintExtension
+        );
+    }
+
+    Ok(())
+}
+
+/// Move transfer fees withheld in `token_account` to the mint, where the
+/// mint's withdraw authority collects them. Token-2022 refuses to close an
+/// account still holding withheld fees; a no-op for mints without a fee.
+fn harvest_withheld_fees<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != anchor_spl::token_2022::ID {
+        return Ok(());
+    }
+    let has_transfer_fee = {
+        let data = mint_info.try_borrow_data()?;
+        let state = StateWithExtensions::<MintState>::unpack(&data)?;
+        state.get_extension_types()?.contains(&ExtensionType::TransferFeeConfig)
+    };
+    if !has_transfer_fee {
+        return Ok(());
+    }
+
+    let cpi_context = CpiContext::new(
+        token_program.to_account_info(),
+        HarvestWithheldTokensToMint {
+            token_program_id: token_program.to_account_info(),
+            mint: mint_info,
+        },
+    );
+    harvest_withheld_tokens_to_mint(cpi_context, vec![token_account.to_account_info()])
+}
+
+/// Resize a program-owned `account` to `space` bytes and keep it exactly
+/// rent-exempt: `payer` covers the extra rent when it grows and gets the
+/// excess back when it shrinks.
+fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let balance = account.lamports();
+    if rent > balance {
+        let cpi_context = CpiContext::new(
+            system_program.to_account_info(),
+            Transfer {
+                from: payer.to_account_info(),
+                to: account.clone(),
+            },
+        );
+        transfer(cpi_context, rent - balance)?;
+    } else if balance > rent {
+        account.sub_lamports(balance - rent)?;
+        payer.add_lamports(balance - rent)?;
+    }
+    account.resize(space)?;
+    Ok(())
+}
+
+/// Unwrap an optional account that mint-priced agents and jobs require.
+fn required<T>(account: &Option<T>) -> Result<&T> {
+    account
+        .as_ref()
+        .ok_or_else(|| error!(ErrorCode::MissingTokenAccount))
+}
+
+/// Token accounts backing the escrow of a job priced in an SPL mint.
+struct EscrowTokens<'a, 'info> {
+    mint: &'a InterfaceAccount<'info, Mint>,
+    escrow_token: &'a InterfaceAccount<'info, TokenAccount>,
+    token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// A job's escrow PDA. Pays out lamports, or tokens from the escrow's token
+/// account for mint-priced jobs.
+struct JobEscrow<'a, 'info> {
+    job_key: Pubkey,
+    bump: u8,
+    escrow: &'a SystemAccount<'info>,
+    system_program: &'a Program<'info, System>,
+    tokens: Option<EscrowTokens<'a, 'info>>,
+}
+
+impl<'a, 'info> JobEscrow<'a, 'info> {
+    fn new(
+        job: &Account<'info, Job>,
+        escrow: &'a SystemAccount<'info>,
+        system_program: &'a Program<'info, System>,
+        mint: &'a Option<InterfaceAccount<'info, Mint>>,
+        escrow_token: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+        token_program: &'a Option<Interface<'info, TokenInterface>>,
+    ) -> Result<Self> {
+        let tokens = match job.mint {
+            None => None,
+            Some(_) => Some(EscrowTokens {
+                mint: required(mint)?,
+                escrow_token: required(escrow_token)?,
+                token_program: required(token_program)?,
+            }),
+        };
+
+        Ok(Self {
+            job_key: job.key(),
+            bump: job.escrow_bump,
+            escrow,
+            system_program,
+            tokens,
+        })
+    }
+
+    /// Amount held for the job: the token balance for mint-priced jobs,
+    /// lamports (price + rent) otherwise.
+    fn balance(&self) -> u64 {
+        match &self.tokens {
+            Some(tokens) => tokens.escrow_token.amount,
+            None => self.escrow.lamports(),
+        }
+    }
+
+    /// Pay `amount` to a wallet, or to its token account for mint-priced jobs.
+    fn pay(
+        &self,
+        to: &AccountInfo<'info>,
+        to_token: &Option<InterfaceAccount<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<()> {
+        let escrow_seeds = &[
+            b"escrow",
+            self.job_key.as_ref(),
+            &[self.bump],
+        ];
+        let signer_seeds = &[&escrow_seeds[..]];
+
+        match &self.tokens {
+            None => {
+                let cpi_context = CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.escrow.to_account_info(),
+                        to: to.clone(),
+                    },
+                    signer_seeds,
+                );
+                transfer(cpi_context, amount)
+            }
+            Some(tokens) => {
+                let cpi_context = CpiContext::new_with_signer(
+                    tokens.token_program.to_account_info(),
+                    TransferChecked {
+                        from: tokens.escrow_token.to_account_info(),
+                        mint: tokens.mint.to_account_info(),
+                        to: required(to_token)?.to_account_info(),
+                        authority: self.escrow.to_account_info(),
+                    },
+                    signer_seeds,
+                );
+                transfer_checked(cpi_context, amount, tokens.mint.decimals)
+            }
+        }
+    }
+
+    /// Pay `amount` like [`Self::pay`], then [`Self::close`] the escrow out
+    /// to the same wallet.
+    fn pay_and_close(
+        &self,
+        to: &AccountInfo<'info>,
+        to_token: &Option<InterfaceAccount<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<()> {
+        self.pay(to, to_token, amount)?;
+        self.close(to)
+    }
+
+    /// Close the escrow token account (if any) and send all remaining
+    /// lamports to `to`. Transfer fees withheld in the token account are
+    /// harvested to the mint first.
+    fn close(&self, to: &AccountInfo<'info>) -> Result<()> {
+        let escrow_seeds = &[
+            b"escrow",
+            self.job_key.as_ref(),
+            &[self.bump],
+        ];
+        let signer_seeds = &[&escrow_seeds[..]];
+
+        if let Some(tokens) = &self.tokens {
+            harvest_withheld_fees(tokens.mint, tokens.escrow_token, tokens.token_program)?;
+            let cpi_context = CpiContext::new_with_signer(
+                tokens.token_program.to_account_info(),
+                CloseAccount {
+                    account: tokens.escrow_token.to_account_info(),
+                    destination: to.clone(),
+                    authority: self.escrow.to_account_info(),
+                },
+                signer_seeds,
+            );
+            close_account(cpi_context)?;
+        }
+
+        let leftover = self.escrow.lamports();
+        if leftover > 0 {
+            let cpi_context = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.escrow.to_account_info(),
+                    to: to.clone(),
+                },
+                signer_seeds,
+            );
+            transfer(cpi_context, leftover)?;
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Contexts
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Platform::INIT_SPACE,
+        seeds = [b"platform"],
+        bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// System-owned PDA that accrues platform fees.
+    /// Zero data; just a lamport vault.
+    #[account(
+        init,
+        payer = authority,
+        space = 0,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetArbiter<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump,
+        has_one = authority
+    )]
+    pub platform: Account<'info, Platform>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump,
+        has_one = authority
+    )]
+    pub platform: Account<'info, Platform>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(category_id: u16)]
+pub struct CreateCategory<'info> {
+    #[account(seeds = [b"platform"], bump, has_one = authority)]
+    pub platform: Account<'info, Platform>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Category::INIT_SPACE,
+        seeds = [b"category".as_ref(), &category_id.to_le_bytes()],
+        bump
+    )]
+    pub category: Account<'info, Category>,
+
+    pub authority: Signer<'info>,
+
+    /// Pays the category's rent, so the authority can stay a multisig PDA.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCategory<'info> {
+    #[account(seeds = [b"platform"], bump, has_one = authority)]
+    pub platform: Account<'info, Platform>,
+
+    #[account(
+        mut,
+        seeds = [b"category".as_ref(), &category.category_id.to_le_bytes()],
+        bump = category.bump
+    )]
+    pub category: Account<'info, Category>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump,
+        has_one = authority
+    )]
+    pub platform: Account<'info, Platform>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(mut, seeds = [b"platform"], bump)]
+    pub platform: Account<'info, Platform>,
+
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePlatformConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump,
+        has_one = authority
+    )]
+    pub platform: Account<'info, Platform>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, skill_uri: String, price_lamports: u64, metadata: AgentMetadataInput)]
+pub struct RegisterAgent<'info> {
+    /// Per-owner counter; its `agents_created` seeds the new agent.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + OwnerRegistry::INIT_SPACE,
+        seeds = [b"owner_registry", owner.key().as_ref()],
+        bump
+    )]
+    pub owner_registry: Account<'info, OwnerRegistry>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Agent::INIT_SPACE,
+        seeds = [b"agent", owner.key().as_ref(), &owner_registry.agents_created.to_le_bytes()],
+        bump
+    )]
+    pub agent: Account<'info, Agent>,
+
+    /// Sized to fit `metadata`; grows or shrinks on update.
+    #[account(
+        init,
+        payer = owner,
+        space = AgentMetadata::space(&metadata),
+        seeds = [b"agent_metadata", agent.key().as_ref()],
+        bump
+    )]
+    pub agent_metadata: Account<'info, AgentMetadata>,
 
     /// System-owned PDA that accumulates earnings for this agent.
     /// Zero data; just a lamport vault.
@@ -243,11 +1669,121 @@ pub struct RegisterAgent<'info> {
         bump
     )]
     pub agent_vault: SystemAccount<'info>,
+
+    #[account(seeds = [b"platform"], bump)]
+    pub platform: Account<'info, Platform>,
     
     #[account(mut)]
     pub owner: Signer<'info>,
     
     pub system_program: Program<'info, System>,
+
+    /// SPL mint the agent is priced in; omit for SOL pricing.
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Agent vault's token account for `mint`. The address is predictable,
+    /// so it may already have been created by someone else.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = agent_vault,
+        associated_token::token_program = token_program
+    )]
+    pub agent_vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    /// Platform treasury PDA (mint-priced agents only).
+    #[account(seeds = [b"treasury"], bump)]
+    pub treasury: Option<SystemAccount<'info>>,
+
+    /// Treasury's token account for `mint`, created for the first agent
+    /// priced in that mint so fees can be paid into it.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAgent<'info> {
+    #[account(
+        mut,
+        seeds = [b"agent", agent.creator.as_ref(), &agent.agent_id.to_le_bytes()],
+        bump = agent.bump,
+        has_one = owner
+    )]
+    pub agent: Account<'info, Agent>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(metadata: AgentMetadataInput)]
+pub struct UpdateAgentMetadata<'info> {
+    #[account(
+        seeds = [b"agent", agent.creator.as_ref(), &agent.agent_id.to_le_bytes()],
+        bump = agent.bump,
+        has_one = owner
+    )]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"agent_metadata", agent.key().as_ref()],
+        bump = agent_metadata.bump
+    )]
+    pub agent_metadata: Account<'info, AgentMetadata>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAgentOperator<'info> {
+    #[account(
+        mut,
+        seeds = [b"agent", agent.creator.as_ref(), &agent.agent_id.to_le_bytes()],
+        bump = agent.bump,
+        has_one = owner
+    )]
+    pub agent: Account<'info, Agent>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAgentOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"agent", agent.creator.as_ref(), &agent.agent_id.to_le_bytes()],
+        bump = agent.bump,
+        has_one = owner
+    )]
+    pub agent: Account<'info, Agent>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAgentOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"agent", agent.creator.as_ref(), &agent.agent_id.to_le_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+
+    pub new_owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
@@ -259,23 +1795,414 @@ pub struct CreateJob<'info> {
         init,
         payer = client,
         space = 8 + Job::INIT_SPACE,
-        seeds = [b"job", agent.key().as_ref(), &agent.jobs_completed.to_le_bytes()],
+        seeds = [b"job", agent.key().as_ref(), &agent.jobs_created.to_le_bytes()],
+        bump
+    )]
+    pub job: Account<'info, Job>,
+    
+    /// System-owned PDA that holds escrowed lamports for this job.
+    #[account(
+        init,
+        payer = client,
+        space = 0,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"platform"], bump)]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(mut)]
+    pub client: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+
+    /// Agent's payment mint (mint-priced agents only).
+    #[account(constraint = agent.payment_mint == Some(mint.key()) @ ErrorCode::InvalidMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = client,
+        token::token_program = token_program
+    )]
+    pub client_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Escrow PDA's token account for `mint`. The address is predictable,
+    /// so it may already have been created by someone else.
+    #[account(
+        init_if_needed,
+        payer = client,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitDeliverable<'info> {
+    #[account(mut, has_one = agent)]
+    pub job: Account<'info, Job>,
+
+    pub agent: Account<'info, Agent>,
+
+    #[account(seeds = [b"platform"], bump)]
+    pub platform: Account<'info, Platform>,
+
+    /// Agent owner or operator.
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteJob<'info> {
+    #[account(mut, has_one = agent, has_one = client)]
+    pub job: Account<'info, Job>,
+    
+    #[account(
+        mut,
+        seeds = [b"agent", agent.creator.as_ref(), &agent.agent_id.to_le_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    /// System-owned PDA holding escrowed lamports for this job.
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump = job.escrow_bump
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// System-owned PDA vault for the agent.
+    #[account(
+        mut,
+        seeds = [b"agent_vault", agent.key().as_ref()],
+        bump
+    )]
+    pub agent_vault: SystemAccount<'info>,
+    
+    #[account(mut, seeds = [b"platform"], bump)]
+    pub platform: Account<'info, Platform>,
+    
+    /// Platform treasury PDA; receives the fee.
+    #[account(mut, seeds = [b"treasury"], bump = platform.treasury_bump)]
+    pub treasury: SystemAccount<'info>,
+    
+    pub client: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+
+    /// Job's payment mint (mint-priced jobs only).
+    #[account(constraint = job.mint == Some(mint.key()) @ ErrorCode::InvalidMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Escrow PDA's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Agent vault's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = agent_vault,
+        associated_token::token_program = token_program
+    )]
+    pub agent_vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeJob<'info> {
+    #[account(mut, has_one = agent)]
+    pub job: Account<'info, Job>,
+
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    /// System-owned PDA holding escrowed lamports for this job.
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump = job.escrow_bump
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// System-owned PDA vault for the agent.
+    #[account(
+        mut,
+        seeds = [b"agent_vault", agent.key().as_ref()],
+        bump
+    )]
+    pub agent_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [b"platform"], bump)]
+    pub platform: Account<'info, Platform>,
+
+    /// Platform treasury PDA; receives the fee.
+    #[account(mut, seeds = [b"treasury"], bump = platform.treasury_bump)]
+    pub treasury: SystemAccount<'info>,
+
+    /// Anyone can crank a job whose review window has closed.
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Job's payment mint (mint-priced jobs only).
+    #[account(constraint = job.mint == Some(mint.key()) @ ErrorCode::InvalidMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Escrow PDA's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Agent vault's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = agent_vault,
+        associated_token::token_program = token_program
+    )]
+    pub agent_vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelJob<'info> {
+    #[account(mut, has_one = agent, has_one = client)]
+    pub job: Account<'info, Job>,
+
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    /// System-owned PDA holding escrowed lamports for this job.
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump = job.escrow_bump
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Job's payment mint (mint-priced jobs only).
+    #[account(mut, constraint = job.mint == Some(mint.key()) @ ErrorCode::InvalidMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Escrow PDA's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = client,
+        token::token_program = token_program
+    )]
+    pub client_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireJob<'info> {
+    #[account(mut, has_one = agent, has_one = client)]
+    pub job: Account<'info, Job>,
+
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    /// System-owned PDA holding escrowed lamports for this job.
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump = job.escrow_bump
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// Client wallet that receives the refund.
+    #[account(mut)]
+    pub client: SystemAccount<'info>,
+
+    /// Anyone can crank an expired job.
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Job's payment mint (mint-priced jobs only).
+    #[account(mut, constraint = job.mint == Some(mint.key()) @ ErrorCode::InvalidMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Escrow PDA's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = client,
+        token::token_program = token_program
+    )]
+    pub client_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct DisputeJob<'info> {
+    #[account(mut, has_one = client)]
+    pub job: Account<'info, Job>,
+
+    pub client: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(mut, has_one = agent, has_one = client)]
+    pub job: Account<'info, Job>,
+
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    /// System-owned PDA holding escrowed lamports for this job.
+    #[account(
+        mut,
+        seeds = [b"escrow", job.key().as_ref()],
+        bump = job.escrow_bump
+    )]
+    pub escrow: SystemAccount<'info>,
+
+    /// System-owned PDA vault for the agent.
+    #[account(
+        mut,
+        seeds = [b"agent_vault", agent.key().as_ref()],
         bump
     )]
+    pub agent_vault: SystemAccount<'info>,
+
+    #[account(mut, seeds = [b"platform"], bump)]
+    pub platform: Account<'info, Platform>,
+
+    /// Platform treasury PDA; receives the fee.
+    #[account(mut, seeds = [b"treasury"], bump = platform.treasury_bump)]
+    pub treasury: SystemAccount<'info>,
+
+    /// Client wallet that receives the refunded share.
+    #[account(mut)]
+    pub client: SystemAccount<'info>,
+
+    /// Platform authority or appointed arbiter.
+    pub arbiter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Job's payment mint (mint-priced jobs only).
+    #[account(mut, constraint = job.mint == Some(mint.key()) @ ErrorCode::InvalidMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Escrow PDA's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Agent vault's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = agent_vault,
+        associated_token::token_program = token_program
+    )]
+    pub agent_vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = client,
+        token::token_program = token_program
+    )]
+    pub client_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct RateJob<'info> {
+    #[account(mut, has_one = agent, has_one = client)]
     pub job: Account<'info, Job>,
     
-    /// System-owned PDA that holds escrowed lamports for this job.
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+    
     #[account(
         init,
         payer = client,
-        space = 0,
-        seeds = [b"escrow", job.key().as_ref()],
+        space = 8 + Review::INIT_SPACE,
+        seeds = [b"review", job.key().as_ref()],
         bump
     )]
-    pub escrow: SystemAccount<'info>,
-    
-    #[account(mut)]
-    pub platform: Account<'info, Platform>,
+    pub review: Account<'info, Review>,
     
     #[account(mut)]
     pub client: Signer<'info>,
@@ -284,17 +2211,10 @@ pub struct CreateJob<'info> {
 }
 
 #[derive(Accounts)]
-pub struct CompleteJob<'info> {
-    #[account(mut)]
+pub struct CloseJob<'info> {
+    #[account(mut, has_one = client, close = client)]
     pub job: Account<'info, Job>,
-    
-    #[account(
-        mut,
-        seeds = [b"agent", agent.owner.as_ref()],
-        bump = agent.bump
-    )]
-    pub agent: Account<'info, Agent>,
-    
+
     /// System-owned PDA holding escrowed lamports for this job.
     #[account(
         mut,
@@ -303,7 +2223,47 @@ pub struct CompleteJob<'info> {
     )]
     pub escrow: SystemAccount<'info>,
 
-    /// System-owned PDA vault for the agent.
+    /// Client paid the job's rent and gets it back.
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Job's payment mint (mint-priced jobs only).
+    #[account(mut, constraint = job.mint == Some(mint.key()) @ ErrorCode::InvalidMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Escrow PDA's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Receives any tokens left in the escrow of a completed job.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = client,
+        token::token_program = token_program
+    )]
+    pub client_token: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawEarnings<'info> {
+    #[account(
+        seeds = [b"agent", agent.creator.as_ref(), &agent.agent_id.to_le_bytes()],
+        bump = agent.bump,
+        has_one = owner
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    /// System-owned PDA vault for the agent
     #[account(
         mut,
         seeds = [b"agent_vault", agent.key().as_ref()],
@@ -312,49 +2272,109 @@ pub struct CompleteJob<'info> {
     pub agent_vault: SystemAccount<'info>,
     
     #[account(mut)]
-    pub platform: Account<'info, Platform>,
-    
-    /// CHECK: Platform authority receives fees
-    #[account(mut)]
-    pub platform_authority: AccountInfo<'info>,
-    
-    pub client: Signer<'info>,
+    pub owner: Signer<'info>,
     
     pub system_program: Program<'info, System>,
+
+    /// Agent's payment mint (mint-priced agents only).
+    #[account(constraint = agent.payment_mint == Some(mint.key()) @ ErrorCode::InvalidMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Agent vault's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = agent_vault,
+        associated_token::token_program = token_program
+    )]
+    pub agent_vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 #[derive(Accounts)]
-pub struct RateJob<'info> {
-    #[account(mut)]
-    pub job: Account<'info, Job>,
-    
-    #[account(mut)]
-    pub agent: Account<'info, Agent>,
-    
+pub struct WithdrawPlatformFees<'info> {
     #[account(
-        init,
-        payer = client,
-        space = 8 + Review::INIT_SPACE,
-        seeds = [b"review", job.key().as_ref()],
-        bump
+        mut,
+        seeds = [b"platform"],
+        bump,
+        has_one = authority
     )]
-    pub review: Account<'info, Review>,
-    
+    pub platform: Account<'info, Platform>,
+
+    /// System-owned PDA holding accrued platform fees
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = platform.treasury_bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    /// Wallet receiving the fees.
     #[account(mut)]
-    pub client: Signer<'info>,
-    
+    pub recipient: SystemAccount<'info>,
+
     pub system_program: Program<'info, System>,
+
+    /// Mint to withdraw; omit to withdraw lamports.
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Treasury's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub recipient_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawEarnings<'info> {
+pub struct CloseAgent<'info> {
+    /// Registry of the wallet that registered the agent.
+    #[account(
+        mut,
+        seeds = [b"owner_registry", agent.creator.as_ref()],
+        bump = owner_registry.bump
+    )]
+    pub owner_registry: Account<'info, OwnerRegistry>,
+
     #[account(
-        seeds = [b"agent", owner.key().as_ref()],
+        mut,
+        seeds = [b"agent", agent.creator.as_ref(), &agent.agent_id.to_le_bytes()],
         bump = agent.bump,
-        has_one = owner
+        has_one = owner,
+        close = owner
     )]
     pub agent: Account<'info, Agent>,
-    
+
+    #[account(
+        mut,
+        seeds = [b"agent_metadata", agent.key().as_ref()],
+        bump = agent_metadata.bump,
+        close = owner
+    )]
+    pub agent_metadata: Account<'info, AgentMetadata>,
+
     /// System-owned PDA vault for the agent
     #[account(
         mut,
@@ -362,11 +2382,34 @@ pub struct WithdrawEarnings<'info> {
         bump
     )]
     pub agent_vault: SystemAccount<'info>,
-    
+
     #[account(mut)]
     pub owner: Signer<'info>,
-    
+
     pub system_program: Program<'info, System>,
+
+    /// Agent's payment mint (mint-priced agents only).
+    #[account(mut, constraint = agent.payment_mint == Some(mint.key()) @ ErrorCode::InvalidMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Agent vault's token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = agent_vault,
+        associated_token::token_program = token_program
+    )]
+    pub agent_vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
 }
 
 // ============================================================================
@@ -377,39 +2420,136 @@ pub struct WithdrawEarnings<'info> {
 #[derive(InitSpace)]
 pub struct Platform {
     pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub arbiter: Pubkey,
     pub platform_fee_bps: u16,  // Basis points (1000 = 10%)
+    pub pending_fee_bps: Option<u16>,  // Queued increase, see update_platform_config
+    pub pending_fee_effective_at: i64,
+    pub review_window_secs: i64,
     pub total_jobs: u64,
-    pub total_volume: u64,
+    pub total_volume: u64,      // Lamports only; token jobs aren't tallied
+    pub fees_collected: u64,    // Lamports only; token fees aren't tallied
+    pub fees_withdrawn: u64,    // Lamports only
+    pub paused: bool,           // Blocks new agents and jobs
+    pub treasury_bump: u8,
 }
 
+/// Tracks the agents registered by one owner wallet.
 #[account]
 #[derive(InitSpace)]
-pub struct Agent {
+pub struct OwnerRegistry {
     pub owner: Pubkey,
+    pub agents_created: u64,  // Monotonic; next agent's `agent_id` seed
+    pub agent_count: u64,     // Agents not yet closed
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Agent {
+    pub owner: Pubkey,                  // Current owner; may change via accept_agent_owner
+    pub creator: Pubkey,                // Registering wallet; fixed, seeds the PDA
+    pub pending_owner: Option<Pubkey>,
+    pub operator: Option<Pubkey>,       // Hot key for job lifecycle actions only
+    pub agent_id: u64,
     #[max_len(50)]
     pub name: String,
     #[max_len(200)]
     pub skill_uri: String,
-    pub price_lamports: u64,
+    pub price_lamports: u64,  // In base units of `payment_mint` when set
+    pub payment_mint: Option<Pubkey>,
+    pub jobs_created: u64,
+    pub open_jobs: u64,  // Jobs not yet completed, cancelled, refunded or resolved
     pub jobs_completed: u64,
     pub total_earned: u64,
     pub rating_sum: u64,
     pub rating_count: u64,
+    pub deadlines_missed: u64,
+    pub disputes_resolved: u64,
+    pub dispute_share_bps_sum: u64,
     pub is_active: bool,
     pub bump: u8,
 }
 
+/// Integration details for an agent, kept out of `Agent` so the account can
+/// be resized to its contents (and grow new fields) without moving `Agent`.
+#[account]
+pub struct AgentMetadata {
+    pub agent: Pubkey,
+    pub endpoint_url: String,               // Webhook new jobs are dispatched to
+    pub description: String,
+    pub tags: Vec<String>,                  // Free-form, up to MAX_TAGS
+    pub categories: Vec<u16>,               // `Category` IDs, up to MAX_CATEGORIES
+    pub encryption_key: Option<[u8; 32]>,   // X25519 key for encrypting task payloads
+    pub bump: u8,
+}
+
+impl AgentMetadata {
+    /// Account size (discriminator included) for `metadata`.
+    pub fn space(metadata: &AgentMetadataInput) -> usize {
+        8 + 32
+            + 4 + metadata.endpoint_url.len()
+            + 4 + metadata.description.len()
+            + 4 + metadata.tags.iter().map(|tag| 4 + tag.len()).sum::<usize>()
+            + 4 + 2 * metadata.categories.len()
+            + 1 + 32
+            + 1
+    }
+
+    fn set(&mut self, metadata: AgentMetadataInput) {
+        self.endpoint_url = metadata.endpoint_url;
+        self.description = metadata.description;
+        self.tags = metadata.tags;
+        self.categories = metadata.categories;
+        self.encryption_key = metadata.encryption_key;
+    }
+}
+
+/// Owner-supplied `AgentMetadata` fields, for `register_agent` and
+/// `update_agent_metadata`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AgentMetadataInput {
+    pub endpoint_url: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub categories: Vec<u16>,
+    pub encryption_key: Option<[u8; 32]>,
+}
+
+/// Platform-curated agent category (e.g. "Email writing").
+#[account]
+#[derive(InitSpace)]
+pub struct Category {
+    pub category_id: u16,  // Chosen by the authority; seeds the PDA
+    #[max_len(50)]
+    pub name: String,
+    pub is_active: bool,   // Inactive categories take no new agents
+    pub agent_count: u32,  // Agents listed under it, closed ones excluded
+    pub bump: u8,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Job {
     pub agent: Pubkey,
+    pub job_id: u64,
     pub client: Pubkey,
     #[max_len(500)]
     pub task_description: String,
-    pub price_lamports: u64,
+    pub price_lamports: u64,  // In base units of `mint` when set
+    pub mint: Option<Pubkey>,
+    pub platform_fee_bps: u16,  // Snapshot of the platform fee at creation
+    pub escrowed_amount: u64,  // What the escrow received (price minus any transfer fee)
     pub status: JobStatus,
     pub created_at: i64,
+    pub deadline: Option<i64>,
+    pub deliverable_hash: Option<[u8; 32]>,
+    #[max_len(200)]
+    pub deliverable_uri: String,
+    pub delivered_at: Option<i64>,
+    pub review_ends_at: Option<i64>,
     pub completed_at: Option<i64>,
+    pub rated: bool,
     pub bump: u8,
     pub escrow_bump: u8,
 }
@@ -426,6 +2566,229 @@ pub struct Review {
     pub created_at: i64,
 }
 
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct PlatformInitialized {
+    pub authority: Pubkey,
+    pub platform_fee_bps: u16,
+    pub review_window_secs: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ArbiterSet {
+    pub arbiter: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlatformPauseSet {
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlatformConfigUpdated {
+    pub platform_fee_bps: u16,
+    pub pending_fee_bps: Option<u16>,
+    pub pending_fee_effective_at: i64,
+    pub review_window_secs: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlatformFeesWithdrawn {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub mint: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CategoryUpdated {
+    pub category: Pubkey,
+    pub category_id: u16,
+    pub name: String,
+    pub is_active: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentRegistered {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub agent_id: u64,
+    pub price_lamports: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub categories: Vec<u16>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentUpdated {
+    pub agent: Pubkey,
+    pub price_lamports: u64,
+    pub is_active: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentOperatorSet {
+    pub agent: Pubkey,
+    pub operator: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentMetadataUpdated {
+    pub agent: Pubkey,
+    pub endpoint_url: String,
+    pub tags: Vec<String>,
+    pub categories: Vec<u16>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentOwnerProposed {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentOwnerTransferred {
+    pub agent: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AgentClosed {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub categories: Vec<u16>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EarningsWithdrawn {
+    pub agent: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub mint: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobCreated {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub job_id: u64,
+    pub price_lamports: u64,
+    pub escrowed_amount: u64,
+    pub mint: Option<Pubkey>,
+    pub platform_fee_bps: u16,
+    pub deadline: Option<i64>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DeliverableSubmitted {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub content_hash: [u8; 32],
+    pub review_ends_at: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted by both `complete_job` and `finalize_job`.
+#[event]
+pub struct JobCompleted {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub agent_amount: u64,
+    pub platform_fee: u64,
+    pub mint: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobCancelled {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub refunded_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobExpired {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub refunded_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobDisputed {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub agent_share_bps: u16,
+    pub agent_amount: u64,
+    pub platform_fee: u64,
+    pub client_amount: u64,
+    pub mint: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobRated {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub rating: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JobClosed {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub client: Pubkey,
+    pub timestamp: i64,
+}
+
 // ============================================================================
 // Enums & Errors
 // ============================================================================
@@ -433,7 +2796,12 @@ pub struct Review {
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
 pub enum JobStatus {
     Created,
+    Delivered,
     Completed,
+    Cancelled,
+    Refunded,
+    Disputed,
+    Resolved,
 }
 
 #[error_code]
@@ -460,4 +2828,60 @@ pub enum ErrorCode {
     InvalidRating,
     #[msg("Insufficient funds")]
     InsufficientFunds,
+    #[msg("Deadline must be in the future")]
+    InvalidDeadline,
+    #[msg("Job has no deadline")]
+    NoDeadline,
+    #[msg("Job deadline has not passed yet")]
+    DeadlineNotReached,
+    #[msg("Invalid share basis points (must be <= 10000)")]
+    InvalidShareBps,
+    #[msg("Signer is not the platform authority or arbiter")]
+    NotArbiter,
+    #[msg("Job deadline has passed")]
+    DeadlinePassed,
+    #[msg("Invalid review window (must be > 0 seconds)")]
+    InvalidReviewWindow,
+    #[msg("Client review window is still open")]
+    ReviewWindowOpen,
+    #[msg("Client review window has closed")]
+    ReviewWindowClosed,
+    #[msg("Mint does not match the agent's payment mint")]
+    InvalidMint,
+    #[msg("Token account required for mint-priced agents")]
+    MissingTokenAccount,
+    #[msg("Mint has an extension that cannot be escrowed safely")]
+    UnsupportedMintExtension,
+    #[msg("Agent still has open jobs")]
+    AgentHasOpenJobs,
+    #[msg("Unrated job can't be closed until the rating grace period ends")]
+    RatingGracePeriod,
+    #[msg("Signer is not the proposed owner")]
+    NotPendingOwner,
+    #[msg("Signer is not the agent owner or operator")]
+    NotOwnerOrOperator,
+    #[msg("Signer is not the proposed authority")]
+    NotPendingAuthority,
+    #[msg("Fee increase exceeds the maximum per change")]
+    FeeIncreaseTooLarge,
+    #[msg("Marketplace is paused")]
+    PlatformPaused,
+    #[msg("Endpoint must be an https URL")]
+    InvalidEndpoint,
+    #[msg("Description too long (max 500 chars)")]
+    DescriptionTooLong,
+    #[msg("Too many tags (max 5)")]
+    TooManyTags,
+    #[msg("Tag must be 1-32 chars")]
+    InvalidTag,
+    #[msg("Too many categories (max 3)")]
+    TooManyCategories,
+    #[msg("Category listed more than once")]
+    DuplicateCategory,
+    #[msg("Category is not accepting agents")]
+    CategoryInactive,
+    #[msg("Category account missing from remaining accounts")]
+    MissingCategoryAccount,
+    #[msg("Agent count would drop below zero")]
+    AgentCountUnderflow,
 }
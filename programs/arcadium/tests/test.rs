@@ -29,6 +29,27 @@ fn anchor_discriminator(ix_name: &str) -> [u8; 8] {
 #[derive(BorshSerialize)]
 struct InitializeArgs {
     platform_fee_bps: u16,
+    review_window_secs: i64,
+}
+
+#[derive(BorshSerialize)]
+struct ProposeAuthorityArgs {
+    new_authority: [u8; 32],
+}
+
+#[derive(BorshSerialize)]
+struct UpdatePlatformConfigArgs {
+    platform_fee_bps: Option<u16>,
+    review_window_secs: Option<i64>,
+}
+
+#[derive(BorshSerialize, Default)]
+struct AgentMetadataArgs {
+    endpoint_url: String,
+    description: String,
+    tags: Vec<String>,
+    categories: Vec<u16>,
+    encryption_key: Option<[u8; 32]>,
 }
 
 #[derive(BorshSerialize)]
@@ -36,11 +57,37 @@ struct RegisterAgentArgs {
     name: String,
     skill_uri: String,
     price_lamports: u64,
+    metadata: AgentMetadataArgs,
+}
+
+#[derive(BorshSerialize)]
+struct UpdateAgentArgs {
+    name: Option<String>,
+    skill_uri: Option<String>,
+    price_lamports: Option<u64>,
+    is_active: Option<bool>,
+}
+
+#[derive(BorshSerialize)]
+struct ProposeAgentOwnerArgs {
+    new_owner: [u8; 32],
+}
+
+#[derive(BorshSerialize)]
+struct SetAgentOperatorArgs {
+    operator: Option<[u8; 32]>,
 }
 
 #[derive(BorshSerialize)]
 struct CreateJobArgs {
     task_description: String,
+    deadline: Option<i64>,
+}
+
+#[derive(BorshSerialize)]
+struct SubmitDeliverableArgs {
+    content_hash: [u8; 32],
+    uri: String,
 }
 
 #[derive(BorshSerialize)]
@@ -64,16 +111,18 @@ fn prints_instruction_encodings_and_pdas() -> Result<()> {
 
     // PDAs
     let (platform_pda, platform_bump) = Pubkey::find_program_address(&[b"platform"], &program_id);
-    let (agent_pda, agent_bump) = Pubkey::find_program_address(&[b"agent", owner.pubkey().as_ref()], &program_id);
+    let (owner_registry_pda, owner_registry_bump) = Pubkey::find_program_address(&[b"owner_registry", owner.pubkey().as_ref()], &program_id);
+    let (agent_pda, agent_bump) = Pubkey::find_program_address(&[b"agent", owner.pubkey().as_ref(), &0u64.to_le_bytes()], &program_id);
     let (agent_vault_pda, agent_vault_bump) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
 
-    // NOTE: job PDA seed in program uses jobs_completed counter; for tests we start at 0.
+    // NOTE: job PDA seed in program uses the agent's jobs_created counter; for tests we start at 0.
     let job_index_bytes = 0u64.to_le_bytes();
     let (job_pda, job_bump) = Pubkey::find_program_address(&[b"job", agent_pda.as_ref(), &job_index_bytes], &program_id);
     let (escrow_pda, escrow_bump) = Pubkey::find_program_address(&[b"escrow", job_pda.as_ref()], &program_id);
     let (review_pda, review_bump) = Pubkey::find_program_address(&[b"review", job_pda.as_ref()], &program_id);
 
     println!("platform_pda: {platform_pda} bump={platform_bump}");
+    println!("owner_registry_pda: {owner_registry_pda} bump={owner_registry_bump}");
     println!("agent_pda: {agent_pda} bump={agent_bump}");
     println!("agent_vault_pda: {agent_vault_pda} bump={agent_vault_bump}");
     println!("job_pda: {job_pda} bump={job_bump}");
@@ -83,7 +132,7 @@ fn prints_instruction_encodings_and_pdas() -> Result<()> {
     // Build instruction datas (discriminator + borsh args)
     let init_ix_data = {
         let mut data = Vec::from(anchor_discriminator("initialize"));
-        data.extend(InitializeArgs { platform_fee_bps: 1000 }.try_to_vec()?);
+        data.extend(InitializeArgs { platform_fee_bps: 1000, review_window_secs: 86_400 }.try_to_vec()?);
         data
     };
     println!("initialize ix data len={} hex={}", init_ix_data.len(), hex::encode(&init_ix_data));
@@ -95,6 +144,7 @@ fn prints_instruction_encodings_and_pdas() -> Result<()> {
                 name: "Sarah's Professional Emails".to_string(),
                 skill_uri: "ipfs://example".to_string(),
                 price_lamports: 10_000_000,
+                metadata: AgentMetadataArgs::default(),
             }
             .try_to_vec()?,
         );
@@ -107,6 +157,7 @@ fn prints_instruction_encodings_and_pdas() -> Result<()> {
         data.extend(
             CreateJobArgs {
                 task_description: "Write an email declining a meeting but offering alternatives".to_string(),
+                deadline: None,
             }
             .try_to_vec()?,
         );
@@ -172,21 +223,25 @@ fn litesvm_happy_path_create_and_complete_job() -> Result<()> {
 
     // Derive PDAs
     let (platform_pda, _platform_bump) = Pubkey::find_program_address(&[b"platform"], &program_id);
-    let (agent_pda, _agent_bump) = Pubkey::find_program_address(&[b"agent", owner.pubkey().as_ref()], &program_id);
+    let (treasury_pda, _) = Pubkey::find_program_address(&[b"treasury"], &program_id);
+    let (owner_registry_pda, _owner_registry_bump) = Pubkey::find_program_address(&[b"owner_registry", owner.pubkey().as_ref()], &program_id);
+    let (agent_pda, _agent_bump) = Pubkey::find_program_address(&[b"agent", owner.pubkey().as_ref(), &0u64.to_le_bytes()], &program_id);
     let (agent_vault_pda, _agent_vault_bump) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
+    let (agent_metadata_pda, _) = Pubkey::find_program_address(&[b"agent_metadata", agent_pda.as_ref()], &program_id);
     let job_index_bytes = 0u64.to_le_bytes();
     let (job_pda, _job_bump) = Pubkey::find_program_address(&[b"job", agent_pda.as_ref(), &job_index_bytes], &program_id);
     let (escrow_pda, _escrow_bump) = Pubkey::find_program_address(&[b"escrow", job_pda.as_ref()], &program_id);
 
     // 1) initialize
     let mut init_data = Vec::from(anchor_discriminator("initialize"));
-    init_data.extend(InitializeArgs { platform_fee_bps: 1000 }.try_to_vec()?);
+    init_data.extend(InitializeArgs { platform_fee_bps: 1000, review_window_secs: 86_400 }.try_to_vec()?);
 
     let init_ix = Instruction {
         program_id,
         accounts: vec![
             AccountMeta::new(platform_pda, false),
             AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(treasury_pda, false),
             AccountMeta::new_readonly(system_program::ID, false),
         ],
         data: init_data,
@@ -206,6 +261,7 @@ fn litesvm_happy_path_create_and_complete_job() -> Result<()> {
             name: "Sarah".to_string(),
             skill_uri: "ipfs://example".to_string(),
             price_lamports: 10_000_000,
+            metadata: AgentMetadataArgs::default(),
         }
         .try_to_vec()?,
     );
@@ -213,8 +269,11 @@ fn litesvm_happy_path_create_and_complete_job() -> Result<()> {
     let reg_ix = Instruction {
         program_id,
         accounts: vec![
+            AccountMeta::new(owner_registry_pda, false),
             AccountMeta::new(agent_pda, false),
+            AccountMeta::new(agent_metadata_pda, false),
             AccountMeta::new(agent_vault_pda, false),
+            AccountMeta::new_readonly(platform_pda, false),
             AccountMeta::new(owner.pubkey(), true),
             AccountMeta::new_readonly(system_program::ID, false),
         ],
@@ -233,6 +292,7 @@ fn litesvm_happy_path_create_and_complete_job() -> Result<()> {
     create_data.extend(
         CreateJobArgs {
             task_description: "Decline meeting".to_string(),
+            deadline: None,
         }
         .try_to_vec()?,
     );
@@ -262,7 +322,35 @@ fn litesvm_happy_path_create_and_complete_job() -> Result<()> {
     let escrow_bal = svm.get_balance(&escrow_pda)?;
     println!("balances after create_job: client={client_bal} escrow={escrow_bal}");
 
-    // 4) complete_job
+    // 4) submit_deliverable (agent owner commits to the delivered work)
+    let mut deliver_data = Vec::from(anchor_discriminator("submit_deliverable"));
+    deliver_data.extend(
+        SubmitDeliverableArgs {
+            content_hash: Sha256::digest(b"Dear Michael, ...").into(),
+            uri: "ipfs://deliverable".to_string(),
+        }
+        .try_to_vec()?,
+    );
+
+    let deliver_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new_readonly(agent_pda, false),
+            AccountMeta::new_readonly(platform_pda, false),
+            AccountMeta::new_readonly(owner.pubkey(), true),
+        ],
+        data: deliver_data,
+    };
+
+    let mut tx = Transaction::new_with_payer(&[deliver_ix], Some(&owner.pubkey()));
+    let bh = svm.latest_blockhash();
+    tx.sign(&[&owner], bh);
+    let res = svm.send_transaction(tx);
+    println!("submit_deliverable result: {res:?}");
+    res?;
+
+    // 5) complete_job
     let complete_ix = Instruction {
         program_id,
         accounts: vec![
@@ -271,7 +359,7 @@ fn litesvm_happy_path_create_and_complete_job() -> Result<()> {
             AccountMeta::new(escrow_pda, false),
             AccountMeta::new(agent_vault_pda, false),
             AccountMeta::new(platform_pda, false),
-            AccountMeta::new(authority.pubkey(), false),
+            AccountMeta::new(treasury_pda, false),
             AccountMeta::new(client.pubkey(), true),
             AccountMeta::new_readonly(system_program::ID, false),
         ],
@@ -288,8 +376,1999 @@ fn litesvm_happy_path_create_and_complete_job() -> Result<()> {
     // Print balances after completion
     let escrow_bal = svm.get_balance(&escrow_pda)?;
     let vault_bal = svm.get_balance(&agent_vault_pda)?;
-    let authority_bal = svm.get_balance(&authority.pubkey())?;
-    println!("balances after complete_job: escrow={escrow_bal} agent_vault={vault_bal} platform_authority={authority_bal}");
+    let treasury_bal = svm.get_balance(&treasury_pda)?;
+    println!("balances after complete_job: escrow={escrow_bal} agent_vault={vault_bal} treasury={treasury_bal}");
+
+    Ok(())
+}
+
+// ============================================================================
+// LiteSVM helpers shared by the lifecycle tests below
+// ============================================================================
+
+/// Load the program and fund an authority, an agent owner and a client.
+fn setup_svm() -> Result<(litesvm::LiteSVM, Keypair, Keypair, Keypair)> {
+    let so_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .join("target")
+        .join("deploy")
+        .join("arcadium.so");
+
+    let mut svm = litesvm::LiteSVM::new();
+    svm.add_program_from_file(arcadium::ID, so_path)?;
+
+    let authority = Keypair::new();
+    let owner = Keypair::new();
+    let client = Keypair::new();
+    svm.airdrop(&authority.pubkey(), 10_000_000_000)?;
+    svm.airdrop(&owner.pubkey(), 10_000_000_000)?;
+    svm.airdrop(&client.pubkey(), 10_000_000_000)?;
+
+    Ok((svm, authority, owner, client))
+}
+
+/// Sign `ix` with `signer` (also the fee payer) and send it.
+fn send_ix(svm: &mut litesvm::LiteSVM, ix: Instruction, signer: &Keypair) -> Result<()> {
+    let mut tx = Transaction::new_with_payer(&[ix], Some(&signer.pubkey()));
+    let bh = svm.latest_blockhash();
+    tx.sign(&[signer], bh);
+    svm.send_transaction(tx)
+        .map_err(|e| anyhow::anyhow!("transaction failed: {e:?}"))?;
+    Ok(())
+}
+
+/// Run `initialize`, `register_agent` and `create_job`, returning `(agent, job, escrow)` PDAs.
+fn setup_open_job(
+    svm: &mut litesvm::LiteSVM,
+    authority: &Keypair,
+    owner: &Keypair,
+    client: &Keypair,
+    deadline: Option<i64>,
+) -> Result<(Pubkey, Pubkey, Pubkey)> {
+    let program_id = arcadium::ID;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    let (treasury_pda, _) = Pubkey::find_program_address(&[b"treasury"], &program_id);
+    let (owner_registry_pda, _) = Pubkey::find_program_address(&[b"owner_registry", owner.pubkey().as_ref()], &program_id);
+    let (agent_pda, _) = Pubkey::find_program_address(&[b"agent", owner.pubkey().as_ref(), &0u64.to_le_bytes()], &program_id);
+    let (agent_vault_pda, _) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
+    let (agent_metadata_pda, _) = Pubkey::find_program_address(&[b"agent_metadata", agent_pda.as_ref()], &program_id);
+    let (job_pda, _) = Pubkey::find_program_address(&[b"job", agent_pda.as_ref(), &0u64.to_le_bytes()], &program_id);
+    let (escrow_pda, _) = Pubkey::find_program_address(&[b"escrow", job_pda.as_ref()], &program_id);
+
+    let mut init_data = Vec::from(anchor_discriminator("initialize"));
+    init_data.extend(InitializeArgs { platform_fee_bps: 1000, review_window_secs: 86_400 }.try_to_vec()?);
+    let init_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(treasury_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: init_data,
+    };
+    send_ix(svm, init_ix, authority)?;
+
+    let mut reg_data = Vec::from(anchor_discriminator("register_agent"));
+    reg_data.extend(
+        RegisterAgentArgs {
+            name: "Sarah".to_string(),
+            skill_uri: "ipfs://example".to_string(),
+            price_lamports: 10_000_000,
+            metadata: AgentMetadataArgs::default(),
+        }
+        .try_to_vec()?,
+    );
+    let reg_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(owner_registry_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(agent_metadata_pda, false),
+            AccountMeta::new(agent_vault_pda, false),
+            AccountMeta::new_readonly(platform_pda, false),
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: reg_data,
+    };
+    send_ix(svm, reg_ix, owner)?;
+
+    let mut create_data = Vec::from(anchor_discriminator("create_job"));
+    create_data.extend(
+        CreateJobArgs {
+            task_description: "Decline meeting".to_string(),
+            deadline,
+        }
+        .try_to_vec()?,
+    );
+    let create_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: create_data,
+    };
+    send_ix(svm, create_ix, client)?;
+
+    Ok((agent_pda, job_pda, escrow_pda))
+}
+
+#[test]
+fn litesvm_cancel_job_refunds_escrow() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, job_pda, escrow_pda) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+
+    let client_before = svm.get_balance(&client.pubkey()).unwrap_or(0);
+    let escrow_before = svm.get_balance(&escrow_pda).unwrap_or(0);
+    assert!(escrow_before >= 10_000_000);
+
+    let cancel_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: Vec::from(anchor_discriminator("cancel_job")),
+    };
+    send_ix(&mut svm, cancel_ix.clone(), &client)?;
+
+    // Escrow is drained; the client gets it all back minus the tx fee.
+    assert_eq!(svm.get_balance(&escrow_pda).unwrap_or(0), 0);
+    let client_after = svm.get_balance(&client.pubkey()).unwrap_or(0);
+    assert!(client_after + 10_000 >= client_before + escrow_before);
+
+    // A cancelled job cannot be cancelled again.
+    svm.expire_blockhash();
+    assert!(send_ix(&mut svm, cancel_ix, &client).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_expire_job_after_deadline_refunds_client() -> Result<()> {
+    use solana_sdk::clock::Clock;
+
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    let (agent_pda, job_pda, escrow_pda) =
+        setup_open_job(&mut svm, &authority, &owner, &client, Some(now + 3600))?;
+
+    // Anyone may crank the expiry; use a fresh keypair.
+    let cranker = Keypair::new();
+    svm.airdrop(&cranker.pubkey(), 1_000_000_000)?;
+
+    let expire_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(client.pubkey(), false),
+            AccountMeta::new_readonly(cranker.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: Vec::from(anchor_discriminator("expire_job")),
+    };
+
+    // Too early: the deadline has not passed.
+    assert!(send_ix(&mut svm, expire_ix.clone(), &cranker).is_err());
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = now + 3601;
+    svm.set_sysvar(&clock);
+
+    let client_before = svm.get_balance(&client.pubkey()).unwrap_or(0);
+    let escrow_before = svm.get_balance(&escrow_pda).unwrap_or(0);
+    svm.expire_blockhash();
+    send_ix(&mut svm, expire_ix, &cranker)?;
+
+    assert_eq!(svm.get_balance(&escrow_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&client.pubkey()).unwrap_or(0), client_before + escrow_before);
+
+    Ok(())
+}
+
+#[derive(BorshSerialize)]
+struct ResolveDisputeArgs {
+    agent_share_bps: u16,
+}
+
+#[test]
+fn litesvm_dispute_resolved_with_split() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, job_pda, escrow_pda) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    let (treasury_pda, _) = Pubkey::find_program_address(&[b"treasury"], &program_id);
+    let (agent_vault_pda, _) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
+
+    let dispute_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new_readonly(client.pubkey(), true),
+        ],
+        data: Vec::from(anchor_discriminator("dispute_job")),
+    };
+    send_ix(&mut svm, dispute_ix, &client)?;
+
+    let resolve_ix = |arbiter: Pubkey| {
+        let mut data = Vec::from(anchor_discriminator("resolve_dispute"));
+        data.extend(ResolveDisputeArgs { agent_share_bps: 5000 }.try_to_vec().unwrap());
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(job_pda, false),
+                AccountMeta::new(agent_pda, false),
+                AccountMeta::new(escrow_pda, false),
+                AccountMeta::new(agent_vault_pda, false),
+                AccountMeta::new(platform_pda, false),
+                AccountMeta::new(treasury_pda, false),
+                AccountMeta::new(client.pubkey(), false),
+                AccountMeta::new_readonly(arbiter, true),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data,
+        }
+    };
+
+    // The client cannot arbitrate their own dispute.
+    assert!(send_ix(&mut svm, resolve_ix(client.pubkey()), &client).is_err());
+
+    let vault_before = svm.get_balance(&agent_vault_pda).unwrap_or(0);
+    let client_before = svm.get_balance(&client.pubkey()).unwrap_or(0);
+    send_ix(&mut svm, resolve_ix(authority.pubkey()), &authority)?;
+
+    // 50% of 0.01 SOL to the agent side, 10% of that to the platform.
+    assert_eq!(svm.get_balance(&agent_vault_pda).unwrap_or(0), vault_before + 4_500_000);
+    assert!(svm.get_balance(&client.pubkey()).unwrap_or(0) >= client_before + 5_000_000);
+    assert_eq!(svm.get_balance(&escrow_pda).unwrap_or(0), 0);
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_finalize_job_after_review_window() -> Result<()> {
+    use solana_sdk::clock::Clock;
+
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, job_pda, escrow_pda) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    let (treasury_pda, _) = Pubkey::find_program_address(&[b"treasury"], &program_id);
+    let (agent_vault_pda, _) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
+
+    let mut deliver_data = Vec::from(anchor_discriminator("submit_deliverable"));
+    deliver_data.extend(
+        SubmitDeliverableArgs {
+            content_hash: [7u8; 32],
+            uri: "ipfs://deliverable".to_string(),
+        }
+        .try_to_vec()?,
+    );
+    let deliver_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new_readonly(agent_pda, false),
+            AccountMeta::new_readonly(platform_pda, false),
+            AccountMeta::new_readonly(owner.pubkey(), true),
+        ],
+        data: deliver_data,
+    };
+    send_ix(&mut svm, deliver_ix, &owner)?;
+
+    // The client walks away; anyone cranks once the window closes.
+    let cranker = Keypair::new();
+    svm.airdrop(&cranker.pubkey(), 1_000_000_000)?;
+    let finalize_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(agent_vault_pda, false),
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(treasury_pda, false),
+            AccountMeta::new_readonly(cranker.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: Vec::from(anchor_discriminator("finalize_job")),
+    };
+    assert!(send_ix(&mut svm, finalize_ix.clone(), &cranker).is_err());
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp += 86_401;
+    svm.set_sysvar(&clock);
+
+    let vault_before = svm.get_balance(&agent_vault_pda).unwrap_or(0);
+    svm.expire_blockhash();
+    send_ix(&mut svm, finalize_ix, &cranker)?;
+    assert_eq!(svm.get_balance(&agent_vault_pda).unwrap_or(0), vault_before + 9_000_000);
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_agent_runs_concurrent_jobs() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, first_job, _) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+
+    // A second client hires the same agent while the first job is still open.
+    let second_client = Keypair::new();
+    svm.airdrop(&second_client.pubkey(), 10_000_000_000)?;
+    let (second_job, _) = Pubkey::find_program_address(&[b"job", agent_pda.as_ref(), &1u64.to_le_bytes()], &program_id);
+    let (second_escrow, _) = Pubkey::find_program_address(&[b"escrow", second_job.as_ref()], &program_id);
+    assert_ne!(first_job, second_job);
+
+    let mut create_data = Vec::from(anchor_discriminator("create_job"));
+    create_data.extend(
+        CreateJobArgs {
+            task_description: "Follow-up email".to_string(),
+            deadline: None,
+        }
+        .try_to_vec()?,
+    );
+    let create_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(second_job, false),
+            AccountMeta::new(second_escrow, false),
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(second_client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: create_data,
+    };
+    send_ix(&mut svm, create_ix, &second_client)?;
+
+    assert!(svm.get_balance(&second_escrow).unwrap_or(0) >= 10_000_000);
+
+    Ok(())
+}
+
+/// Create a 6-decimal Token-2022 mint sized for `extensions`.
+/// `init_extensions` gets the mint and authority and returns the extension
+/// instructions that must run before `InitializeMint2`.
+fn create_token_2022_mint(
+    svm: &mut litesvm::LiteSVM,
+    authority: &Keypair,
+    extensions: &[anchor_spl::token_2022::spl_token_2022::extension::ExtensionType],
+    init_extensions: impl FnOnce(
+        &anchor_lang::prelude::Pubkey,
+        &anchor_lang::prelude::Pubkey,
+    ) -> Result<Vec<anchor_lang::solana_program::instruction::Instruction>>,
+) -> Result<Pubkey> {
+    use anchor_lang::prelude::Pubkey as AnchorPubkey;
+    use anchor_spl::token_2022::spl_token_2022::{self, extension::ExtensionType, state::Mint};
+
+    let mint = Keypair::new().pubkey();
+    let space = ExtensionType::try_calculate_account_len::<Mint>(extensions)?;
+    svm.set_account(
+        mint,
+        solana_sdk::account::Account {
+            lamports: svm.minimum_balance_for_rent_exemption(space),
+            data: vec![0; space],
+            owner: Pubkey::new_from_array(spl_token_2022::ID.to_bytes()),
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .map_err(|e| anyhow::anyhow!("set mint account: {e:?}"))?;
+
+    let mint_key = AnchorPubkey::new_from_array(mint.to_bytes());
+    let authority_key = AnchorPubkey::new_from_array(authority.pubkey().to_bytes());
+    let mut ixs = init_extensions(&mint_key, &authority_key)?;
+    ixs.push(spl_token_2022::instruction::initialize_mint2(
+        &spl_token_2022::ID,
+        &mint_key,
+        &authority_key,
+        None,
+        6,
+    )?);
+    for ix in ixs {
+        send_ix(svm, sdk_ix(ix), authority)?;
+    }
+    Ok(mint)
+}
+
+#[test]
+fn litesvm_transfer_fee_mint_escrows_net_amount() -> Result<()> {
+    use anchor_lang::AccountDeserialize;
+    use anchor_spl::token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{instruction::initialize_transfer_fee_config, TransferFeeConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        state::Mint as MintState,
+    };
+    use litesvm_token::{
+        get_spl_account, spl_token::state::Account as TokenAccount, CreateAssociatedTokenAccount, MintTo,
+    };
+
+    let program_id = Pubkey::new_from_array(arcadium::ID.to_bytes());
+    let token_program = Pubkey::new_from_array(spl_token_2022::ID.to_bytes());
+    let ata_program: Pubkey = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL".parse()?;
+    let ata = |owner: &Pubkey, mint: &Pubkey| {
+        Pubkey::find_program_address(&[owner.as_ref(), token_program.as_ref(), mint.as_ref()], &ata_program).0
+    };
+    let (mut svm, authority, owner, client) = setup_svm()?;
+
+    // 1% fee on every transfer, withheld in the receiving account
+    let mint = create_token_2022_mint(&mut svm, &authority, &[ExtensionType::TransferFeeConfig], |mint, authority| {
+        Ok(vec![initialize_transfer_fee_config(&spl_token_2022::ID, mint, Some(authority), Some(authority), 100, u64::MAX)?])
+    })?;
+    let client_token = CreateAssociatedTokenAccount::new(&mut svm, &client, &mint).token_program_id(&token_program).send()
+        .map_err(|e| anyhow::anyhow!("create client ata: {e:?}"))?;
+    MintTo::new(&mut svm, &authority, &mint, &client_token, 100_000_000).token_program_id(&token_program).send()
+        .map_err(|e| anyhow::anyhow!("mint to: {e:?}"))?;
+
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    let (owner_registry_pda, _) = Pubkey::find_program_address(&[b"owner_registry", owner.pubkey().as_ref()], &program_id);
+    let (agent_pda, _) = Pubkey::find_program_address(&[b"agent", owner.pubkey().as_ref(), &0u64.to_le_bytes()], &program_id);
+    let (agent_metadata_pda, _) = Pubkey::find_program_address(&[b"agent_metadata", agent_pda.as_ref()], &program_id);
+    let (agent_vault_pda, _) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
+    let vault_token = ata(&agent_vault_pda, &mint);
+    let (treasury_pda, _) = Pubkey::find_program_address(&[b"treasury"], &program_id);
+    let treasury_token = ata(&treasury_pda, &mint);
+
+    let mut init_data = Vec::from(anchor_discriminator("initialize"));
+    init_data.extend(borsh::to_vec(&InitializeArgs { platform_fee_bps: 1000, review_window_secs: 86_400 })?);
+    let init_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(treasury_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: init_data,
+    };
+    send_ix(&mut svm, init_ix, &authority)?;
+
+    let mut reg_data = Vec::from(anchor_discriminator("register_agent"));
+    reg_data.extend(borsh::to_vec(&RegisterAgentArgs {
+        name: "Sarah".to_string(),
+        skill_uri: "ipfs://example".to_string(),
+        price_lamports: 10_000_000,
+        metadata: AgentMetadataArgs::default(),
+    })?);
+    let reg_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(owner_registry_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(agent_metadata_pda, false),
+            AccountMeta::new(agent_vault_pda, false),
+            AccountMeta::new_readonly(platform_pda, false),
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(vault_token, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(ata_program, false),
+            AccountMeta::new_readonly(treasury_pda, false),
+            AccountMeta::new(treasury_token, false),
+        ],
+        data: reg_data,
+    };
+    send_ix(&mut svm, reg_ix, &owner)?;
+
+    // Create job `job_id`, returning its PDA, escrow and escrow token account.
+    let create_job = |svm: &mut litesvm::LiteSVM, job_id: u64| -> Result<(Pubkey, Pubkey, Pubkey)> {
+        let (job_pda, _) = Pubkey::find_program_address(&[b"job", agent_pda.as_ref(), &job_id.to_le_bytes()], &program_id);
+        let (escrow_pda, _) = Pubkey::find_program_address(&[b"escrow", job_pda.as_ref()], &program_id);
+        let escrow_token = ata(&escrow_pda, &mint);
+        let mut data = Vec::from(anchor_discriminator("create_job"));
+        data.extend(borsh::to_vec(&CreateJobArgs { task_description: "Task".to_string(), deadline: None })?);
+        let ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(agent_pda, false),
+                AccountMeta::new(job_pda, false),
+                AccountMeta::new(escrow_pda, false),
+                AccountMeta::new(platform_pda, false),
+                AccountMeta::new(client.pubkey(), true),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(client_token, false),
+                AccountMeta::new(escrow_token, false),
+                AccountMeta::new_readonly(token_program, false),
+                AccountMeta::new_readonly(ata_program, false),
+            ],
+            data,
+        };
+        send_ix(svm, ix, &client)?;
+
+        // The escrow holds the price net of the fee, and payouts split that
+        let job = arcadium::Job::try_deserialize(&mut svm.get_account(&job_pda).expect("job").data.as_slice())?;
+        assert_eq!(job.escrowed_amount, 9_900_000);
+        Ok((job_pda, escrow_pda, escrow_token))
+    };
+    let amount = |svm: &litesvm::LiteSVM, account: &Pubkey| -> Result<u64> {
+        let account: TokenAccount = get_spl_account(svm, account).map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(account.amount)
+    };
+    let harvested = |svm: &litesvm::LiteSVM| -> Result<u64> {
+        let account = svm.get_account(&mint).expect("mint");
+        let state = StateWithExtensions::<MintState>::unpack(&account.data)?;
+        Ok(u64::from(state.get_extension::<TransferFeeConfig>()?.withheld_amount))
+    };
+
+    // Cancel refunds everything escrowed; closing the escrow's token account
+    // harvests the fee withheld on the way in
+    let (job_pda, escrow_pda, escrow_token) = create_job(&mut svm, 0)?;
+    let cancel_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(escrow_token, false),
+            AccountMeta::new(client_token, false),
+            AccountMeta::new_readonly(token_program, false),
+        ],
+        data: Vec::from(anchor_discriminator("cancel_job")),
+    };
+    send_ix(&mut svm, cancel_ix, &client)?;
+    assert_eq!(amount(&svm, &client_token)?, 100_000_000 - 10_000_000 + 9_801_000);
+    assert!(svm.get_account(&escrow_token).is_none_or(|account| account.data.is_empty()));
+    assert_eq!(harvested(&svm)?, 100_000);
+
+    // A 50/50 split of the escrowed amount, 10% platform fee on the agent's half
+    let (job_pda, escrow_pda, escrow_token) = create_job(&mut svm, 1)?;
+    let dispute_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new_readonly(client.pubkey(), true),
+        ],
+        data: Vec::from(anchor_discriminator("dispute_job")),
+    };
+    send_ix(&mut svm, dispute_ix, &client)?;
+    let mut resolve_data = Vec::from(anchor_discriminator("resolve_dispute"));
+    resolve_data.extend(5000u16.to_le_bytes());
+    let resolve_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(agent_vault_pda, false),
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(treasury_pda, false),
+            AccountMeta::new(client.pubkey(), false),
+            AccountMeta::new_readonly(authority.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(escrow_token, false),
+            AccountMeta::new(vault_token, false),
+            AccountMeta::new(treasury_token, false),
+            AccountMeta::new(client_token, false),
+            AccountMeta::new_readonly(token_program, false),
+        ],
+        data: resolve_data,
+    };
+    send_ix(&mut svm, resolve_ix, &authority)?;
+    assert_eq!(amount(&svm, &vault_token)?, 4_455_000 - 44_550);
+    assert_eq!(amount(&svm, &treasury_token)?, 495_000 - 4_950);
+    assert_eq!(amount(&svm, &client_token)?, 89_801_000 + 4_950_000 - 49_500);
+    assert!(svm.get_account(&escrow_token).is_none_or(|account| account.data.is_empty()));
+    assert_eq!(harvested(&svm)?, 200_000);
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_register_agent_rejects_unsafe_mint_extensions() -> Result<()> {
+    use anchor_spl::token_2022::spl_token_2022::{self, extension::ExtensionType, instruction};
+
+    let program_id = Pubkey::new_from_array(arcadium::ID.to_bytes());
+    let token_program = Pubkey::new_from_array(spl_token_2022::ID.to_bytes());
+    let ata_program: Pubkey = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL".parse()?;
+    let (mut svm, authority, owner, _client) = setup_svm()?;
+
+    let permanent_delegate = create_token_2022_mint(&mut svm, &authority, &[ExtensionType::PermanentDelegate], |mint, authority| {
+        Ok(vec![instruction::initialize_permanent_delegate(&spl_token_2022::ID, mint, authority)?])
+    })?;
+    let non_transferable = create_token_2022_mint(&mut svm, &authority, &[ExtensionType::NonTransferable], |mint, _| {
+        Ok(vec![instruction::initialize_non_transferable_mint(&spl_token_2022::ID, mint)?])
+    })?;
+
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    let (owner_registry_pda, _) = Pubkey::find_program_address(&[b"owner_registry", owner.pubkey().as_ref()], &program_id);
+    let (agent_pda, _) = Pubkey::find_program_address(&[b"agent", owner.pubkey().as_ref(), &0u64.to_le_bytes()], &program_id);
+    let (agent_metadata_pda, _) = Pubkey::find_program_address(&[b"agent_metadata", agent_pda.as_ref()], &program_id);
+    let (agent_vault_pda, _) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
+    let (treasury_pda, _) = Pubkey::find_program_address(&[b"treasury"], &program_id);
+
+    let mut init_data = Vec::from(anchor_discriminator("initialize"));
+    init_data.extend(borsh::to_vec(&InitializeArgs { platform_fee_bps: 1000, review_window_secs: 86_400 })?);
+    let init_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(treasury_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: init_data,
+    };
+    send_ix(&mut svm, init_ix, &authority)?;
+
+    for mint in [permanent_delegate, non_transferable] {
+        let (vault_token, _) = Pubkey::find_program_address(
+            &[agent_vault_pda.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &ata_program,
+        );
+        let (treasury_token, _) = Pubkey::find_program_address(
+            &[treasury_pda.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &ata_program,
+        );
+        let mut data = Vec::from(anchor_discriminator("register_agent"));
+        data.extend(borsh::to_vec(&RegisterAgentArgs {
+            name: "Sarah".to_string(),
+            skill_uri: "ipfs://example".to_string(),
+            price_lamports: 10_000_000,
+            metadata: AgentMetadataArgs::default(),
+        })?);
+        let reg_ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(owner_registry_pda, false),
+                AccountMeta::new(agent_pda, false),
+                AccountMeta::new(agent_metadata_pda, false),
+                AccountMeta::new(agent_vault_pda, false),
+                AccountMeta::new_readonly(platform_pda, false),
+                AccountMeta::new(owner.pubkey(), true),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(vault_token, false),
+                AccountMeta::new_readonly(token_program, false),
+                AccountMeta::new_readonly(ata_program, false),
+                AccountMeta::new_readonly(treasury_pda, false),
+                AccountMeta::new(treasury_token, false),
+            ],
+            data,
+        };
+        let err = send_ix(&mut svm, reg_ix, &owner).unwrap_err();
+        assert!(err.to_string().contains("UnsupportedMintExtension"), "{err}");
+    }
+
+    Ok(())
+}
+
+/// Associated token account address for `owner` and `mint` (classic SPL Token).
+fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let ata_program: Pubkey = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL".parse().unwrap();
+    Pubkey::find_program_address(
+        &[owner.as_ref(), litesvm_token::spl_token::ID.as_ref(), mint.as_ref()],
+        &ata_program,
+    )
+    .0
+}
+
+#[test]
+fn litesvm_spl_token_job_pays_out_in_mint() -> Result<()> {
+    use anchor_lang::AccountDeserialize;
+    use litesvm_token::{
+        get_spl_account, spl_token::state::Account as TokenAccount, CreateAssociatedTokenAccount,
+        CreateMint, MintTo,
+    };
+
+    let program_id = arcadium::ID;
+    let token_program = litesvm_token::spl_token::ID;
+    let ata_program: Pubkey = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL".parse()?;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+
+    // A 6-decimal "USDC" mint; the client holds 100 USDC.
+    let mint = CreateMint::new(&mut svm, &authority).decimals(6).send()
+        .map_err(|e| anyhow::anyhow!("create mint: {e:?}"))?;
+    let client_token = CreateAssociatedTokenAccount::new(&mut svm, &client, &mint).send()
+        .map_err(|e| anyhow::anyhow!("create client ata: {e:?}"))?;
+    MintTo::new(&mut svm, &authority, &mint, &client_token, 100_000_000).send()
+        .map_err(|e| anyhow::anyhow!("mint to: {e:?}"))?;
+
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    let (treasury_pda, _) = Pubkey::find_program_address(&[b"treasury"], &program_id);
+    let (owner_registry_pda, _) = Pubkey::find_program_address(&[b"owner_registry", owner.pubkey().as_ref()], &program_id);
+    let (agent_pda, _) = Pubkey::find_program_address(&[b"agent", owner.pubkey().as_ref(), &0u64.to_le_bytes()], &program_id);
+    let (agent_vault_pda, _) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
+    let (agent_metadata_pda, _) = Pubkey::find_program_address(&[b"agent_metadata", agent_pda.as_ref()], &program_id);
+    let (job_pda, _) = Pubkey::find_program_address(&[b"job", agent_pda.as_ref(), &0u64.to_le_bytes()], &program_id);
+    let (escrow_pda, _) = Pubkey::find_program_address(&[b"escrow", job_pda.as_ref()], &program_id);
+    let vault_token = associated_token_address(&agent_vault_pda, &mint);
+    let escrow_token = associated_token_address(&escrow_pda, &mint);
+    let treasury_token = associated_token_address(&treasury_pda, &mint);
+
+    let mut init_data = Vec::from(anchor_discriminator("initialize"));
+    init_data.extend(InitializeArgs { platform_fee_bps: 1000, review_window_secs: 86_400 }.try_to_vec()?);
+    let init_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(treasury_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: init_data,
+    };
+    send_ix(&mut svm, init_ix, &authority)?;
+
+    // Register an agent priced at 5 USDC.
+    let mut reg_data = Vec::from(anchor_discriminator("register_agent"));
+    reg_data.extend(
+        RegisterAgentArgs {
+            name: "Sarah".to_string(),
+            skill_uri: "ipfs://example".to_string(),
+            price_lamports: 5_000_000,
+            metadata: AgentMetadataArgs::default(),
+        }
+        .try_to_vec()?,
+    );
+    let reg_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(owner_registry_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(agent_metadata_pda, false),
+            AccountMeta::new(agent_vault_pda, false),
+            AccountMeta::new_readonly(platform_pda, false),
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(vault_token, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(ata_program, false),
+            AccountMeta::new_readonly(treasury_pda, false),
+            AccountMeta::new(treasury_token, false),
+        ],
+        data: reg_data,
+    };
+    send_ix(&mut svm, reg_ix, &owner)?;
+
+    let mut create_data = Vec::from(anchor_discriminator("create_job"));
+    create_data.extend(
+        CreateJobArgs {
+            task_description: "Decline meeting".to_string(),
+            deadline: None,
+        }
+        .try_to_vec()?,
+    );
+    let create_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(client_token, false),
+            AccountMeta::new(escrow_token, false),
+            AccountMeta::new_readonly(token_program, false),
+            AccountMeta::new_readonly(ata_program, false),
+        ],
+        data: create_data,
+    };
+    send_ix(&mut svm, create_ix, &client)?;
+
+    let escrowed: TokenAccount = get_spl_account(&svm, &escrow_token).map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    assert_eq!(escrowed.amount, 5_000_000);
+
+    let mut deliver_data = Vec::from(anchor_discriminator("submit_deliverable"));
+    deliver_data.extend(
+        SubmitDeliverableArgs {
+            content_hash: [1u8; 32],
+            uri: "ipfs://deliverable".to_string(),
+        }
+        .try_to_vec()?,
+    );
+    let deliver_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new_readonly(agent_pda, false),
+            AccountMeta::new_readonly(platform_pda, false),
+            AccountMeta::new_readonly(owner.pubkey(), true),
+        ],
+        data: deliver_data,
+    };
+    send_ix(&mut svm, deliver_ix, &owner)?;
+
+    let complete_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(agent_vault_pda, false),
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(treasury_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(escrow_token, false),
+            AccountMeta::new(vault_token, false),
+            AccountMeta::new(treasury_token, false),
+            AccountMeta::new_readonly(token_program, false),
+        ],
+        data: Vec::from(anchor_discriminator("complete_job")),
+    };
+    send_ix(&mut svm, complete_ix, &client)?;
+
+    // 90% to the agent vault, 10% to the treasury, in USDC.
+    let vault: TokenAccount = get_spl_account(&svm, &vault_token).map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    let fees: TokenAccount = get_spl_account(&svm, &treasury_token).map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    assert_eq!(vault.amount, 4_500_000);
+    assert_eq!(fees.amount, 500_000);
+
+    // Platform totals are in lamports, so token jobs leave them untouched.
+    let platform = arcadium::Platform::try_deserialize(&mut svm.get_account(&platform_pda).expect("platform").data.as_slice())?;
+    assert_eq!(platform.total_volume, 0);
+    assert_eq!(platform.fees_collected, 0);
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_token_job_survives_precreated_atas() -> Result<()> {
+    use anchor_lang::prelude::Pubkey as AnchorPubkey;
+    use arcadium_client::{instructions as ix, pda, PaymentMint};
+    use litesvm_token::{
+        get_spl_account, spl_token::state::Account as TokenAccount, CreateAssociatedTokenAccount,
+        CreateMint, MintTo,
+    };
+
+    let key = |keypair: &Keypair| AnchorPubkey::new_from_array(keypair.pubkey().to_bytes());
+    let sdk_key = |pubkey: AnchorPubkey| Pubkey::new_from_array(pubkey.to_bytes());
+    let (mut svm, authority, owner, client) = setup_svm()?;
+
+    let mint = CreateMint::new(&mut svm, &authority).decimals(6).send()
+        .map_err(|e| anyhow::anyhow!("create mint: {e:?}"))?;
+    let payment = PaymentMint {
+        mint: AnchorPubkey::new_from_array(mint.to_bytes()),
+        token_program: AnchorPubkey::new_from_array(litesvm_token::spl_token::ID.to_bytes()),
+    };
+    let client_token = CreateAssociatedTokenAccount::new(&mut svm, &client, &mint).send()
+        .map_err(|e| anyhow::anyhow!("create client ata: {e:?}"))?;
+    MintTo::new(&mut svm, &authority, &mint, &client_token, 100_000_000).send()
+        .map_err(|e| anyhow::anyhow!("mint to: {e:?}"))?;
+
+    // Any wallet can create the vault's and next escrow's ATAs up front and
+    // seed the escrow with tokens of its own.
+    let agent = pda::agent(&key(&owner), 0).0;
+    let job = pda::job(&agent, 0).0;
+    let vault_owner = sdk_key(pda::agent_vault(&agent).0);
+    let escrow_owner = sdk_key(pda::escrow(&job).0);
+    let vault_token = CreateAssociatedTokenAccount::new(&mut svm, &authority, &mint).owner(&vault_owner).send()
+        .map_err(|e| anyhow::anyhow!("create vault ata: {e:?}"))?;
+    let escrow_token = CreateAssociatedTokenAccount::new(&mut svm, &authority, &mint).owner(&escrow_owner).send()
+        .map_err(|e| anyhow::anyhow!("create escrow ata: {e:?}"))?;
+    MintTo::new(&mut svm, &authority, &mint, &escrow_token, 1_000_000).send()
+        .map_err(|e| anyhow::anyhow!("mint to: {e:?}"))?;
+
+    send_ix(&mut svm, sdk_ix(ix::initialize(key(&authority), 1000, 86_400)), &authority)?;
+    send_ix(
+        &mut svm,
+        sdk_ix(ix::register_agent(key(&owner), 0, "Sarah".to_string(), "ipfs://example".to_string(), 5_000_000, Default::default(), Some(payment))),
+        &owner,
+    )?;
+    send_ix(&mut svm, sdk_ix(ix::create_job(key(&client), agent, 0, "Task".to_string(), None, Some(payment))), &client)?;
+
+    // The pre-seeded tokens don't count as payment
+    let stored = arcadium_client::decode_job(&svm.get_account(&sdk_key(job)).expect("job").data)?;
+    assert_eq!(stored.escrowed_amount, 5_000_000);
+
+    send_ix(&mut svm, sdk_ix(ix::submit_deliverable(key(&owner), agent, job, [1u8; 32], "ipfs://out".to_string())), &owner)?;
+    send_ix(&mut svm, sdk_ix(ix::complete_job(key(&client), agent, job, Some(payment))), &client)?;
+    let vault: TokenAccount = get_spl_account(&svm, &vault_token).map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    assert_eq!(vault.amount, 4_500_000);
+
+    // Closing hands the stray tokens to the client instead of failing
+    send_ix(&mut svm, sdk_ix(ix::close_job(key(&client), job, Some(payment))), &client)?;
+    let client_account: TokenAccount = get_spl_account(&svm, &client_token).map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    assert_eq!(client_account.amount, 100_000_000 - 5_000_000 + 1_000_000);
+    assert!(svm.get_account(&escrow_token).is_none_or(|account| account.data.is_empty()));
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_update_agent_price_keeps_job_snapshot() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, _first_job, first_escrow) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    let first_escrow_before = svm.get_balance(&first_escrow).unwrap_or(0);
+
+    let update_ix = |args: UpdateAgentArgs| {
+        let mut data = Vec::from(anchor_discriminator("update_agent"));
+        data.extend(args.try_to_vec().unwrap());
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(agent_pda, false),
+                AccountMeta::new_readonly(owner.pubkey(), true),
+            ],
+            data,
+        }
+    };
+
+    // Same validation as registration.
+    let too_long = UpdateAgentArgs {
+        name: Some("x".repeat(51)),
+        skill_uri: None,
+        price_lamports: None,
+        is_active: None,
+    };
+    assert!(send_ix(&mut svm, update_ix(too_long), &owner).is_err());
+
+    let double_price = UpdateAgentArgs {
+        name: None,
+        skill_uri: None,
+        price_lamports: Some(20_000_000),
+        is_active: None,
+    };
+    send_ix(&mut svm, update_ix(double_price), &owner)?;
+
+    // New jobs pay the new price; the open job's escrow is untouched.
+    let (second_job, _) = Pubkey::find_program_address(&[b"job", agent_pda.as_ref(), &1u64.to_le_bytes()], &program_id);
+    let (second_escrow, _) = Pubkey::find_program_address(&[b"escrow", second_job.as_ref()], &program_id);
+    let mut create_data = Vec::from(anchor_discriminator("create_job"));
+    create_data.extend(
+        CreateJobArgs {
+            task_description: "Follow-up email".to_string(),
+            deadline: None,
+        }
+        .try_to_vec()?,
+    );
+    let create_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(second_job, false),
+            AccountMeta::new(second_escrow, false),
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: create_data,
+    };
+    send_ix(&mut svm, create_ix, &client)?;
+
+    assert_eq!(svm.get_balance(&first_escrow).unwrap_or(0), first_escrow_before);
+    assert_eq!(
+        svm.get_balance(&second_escrow).unwrap_or(0),
+        first_escrow_before + 10_000_000
+    );
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_close_agent_only_without_open_jobs() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, job_pda, escrow_pda) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (owner_registry_pda, _) = Pubkey::find_program_address(&[b"owner_registry", owner.pubkey().as_ref()], &program_id);
+    let (agent_vault_pda, _) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
+    let (agent_metadata_pda, _) = Pubkey::find_program_address(&[b"agent_metadata", agent_pda.as_ref()], &program_id);
+
+    let close_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(owner_registry_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(agent_metadata_pda, false),
+            AccountMeta::new(agent_vault_pda, false),
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: Vec::from(anchor_discriminator("close_agent")),
+    };
+
+    // The job is still open.
+    assert!(send_ix(&mut svm, close_ix.clone(), &owner).is_err());
+
+    let cancel_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: Vec::from(anchor_discriminator("cancel_job")),
+    };
+    send_ix(&mut svm, cancel_ix, &client)?;
+
+    let owner_before = svm.get_balance(&owner.pubkey()).unwrap_or(0);
+    let reclaimable = svm.get_balance(&agent_pda).unwrap_or(0) + svm.get_balance(&agent_vault_pda).unwrap_or(0);
+    svm.expire_blockhash();
+    send_ix(&mut svm, close_ix, &owner)?;
+
+    assert!(svm.get_account(&agent_pda).is_none());
+    assert_eq!(svm.get_balance(&agent_vault_pda).unwrap_or(0), 0);
+    assert!(svm.get_balance(&owner.pubkey()).unwrap_or(0) + 10_000 >= owner_before + reclaimable);
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_close_job_after_cancel_refunds_rent() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, job_pda, escrow_pda) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+
+    let close_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: Vec::from(anchor_discriminator("close_job")),
+    };
+
+    // Open jobs can't be closed.
+    assert!(send_ix(&mut svm, close_ix.clone(), &client).is_err());
+
+    let cancel_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: Vec::from(anchor_discriminator("cancel_job")),
+    };
+    send_ix(&mut svm, cancel_ix, &client)?;
+
+    let client_before = svm.get_balance(&client.pubkey()).unwrap_or(0);
+    let job_rent = svm.get_balance(&job_pda).unwrap_or(0);
+    svm.expire_blockhash();
+    send_ix(&mut svm, close_ix, &client)?;
+
+    assert!(svm.get_account(&job_pda).is_none());
+    assert!(svm.get_balance(&client.pubkey()).unwrap_or(0) + 10_000 >= client_before + job_rent);
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_close_completed_job_waits_for_rating() -> Result<()> {
+    use anchor_lang::prelude::Pubkey as AnchorPubkey;
+    use arcadium_client::{instructions as ix, pda};
+    use solana_sdk::clock::Clock;
+
+    let key = |keypair: &Keypair| AnchorPubkey::new_from_array(keypair.pubkey().to_bytes());
+    let sdk_key = |pubkey: AnchorPubkey| Pubkey::new_from_array(pubkey.to_bytes());
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    send_ix(&mut svm, sdk_ix(ix::initialize(key(&authority), 1000, 86_400)), &authority)?;
+    send_ix(
+        &mut svm,
+        sdk_ix(ix::register_agent(key(&owner), 0, "Sarah".to_string(), "ipfs://example".to_string(), 10_000_000, Default::default(), None)),
+        &owner,
+    )?;
+    let agent = pda::agent(&key(&owner), 0).0;
+
+    let complete = |svm: &mut litesvm::LiteSVM, job_id: u64| -> Result<AnchorPubkey> {
+        let job = pda::job(&agent, job_id).0;
+        send_ix(svm, sdk_ix(ix::create_job(key(&client), agent, job_id, "Task".to_string(), None, None)), &client)?;
+        send_ix(svm, sdk_ix(ix::submit_deliverable(key(&owner), agent, job, [1u8; 32], "ipfs://out".to_string())), &owner)?;
+        send_ix(svm, sdk_ix(ix::complete_job(key(&client), agent, job, None)), &client)?;
+        Ok(job)
+    };
+    let rated = complete(&mut svm, 0)?;
+    let unrated = complete(&mut svm, 1)?;
+
+    // A completed job stays open for the client's rating...
+    assert!(send_ix(&mut svm, sdk_ix(ix::close_job(key(&client), rated, None)), &client).is_err());
+    assert!(send_ix(&mut svm, sdk_ix(ix::close_job(key(&client), unrated, None)), &client).is_err());
+
+    // ...which only the job's own client can leave
+    let stranger = Keypair::new();
+    svm.airdrop(&stranger.pubkey(), 1_000_000_000).map_err(|e| anyhow::anyhow!("airdrop: {e:?}"))?;
+    assert!(send_ix(&mut svm, sdk_ix(ix::rate_job(key(&stranger), agent, rated, 5, "Great".to_string())), &stranger).is_err());
+
+    send_ix(&mut svm, sdk_ix(ix::rate_job(key(&client), agent, rated, 5, "Great".to_string())), &client)?;
+    svm.expire_blockhash();
+    send_ix(&mut svm, sdk_ix(ix::close_job(key(&client), rated, None)), &client)?;
+    assert!(svm.get_account(&sdk_key(rated)).is_none());
+
+    // ...until the grace period runs out
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp += arcadium::RATING_GRACE_PERIOD_SECS;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+    send_ix(&mut svm, sdk_ix(ix::close_job(key(&client), unrated, None)), &client)?;
+    assert!(svm.get_account(&sdk_key(unrated)).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_owner_registers_multiple_agents() -> Result<()> {
+    use anchor_lang::AccountDeserialize;
+
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (first_agent, _, _) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    let (owner_registry_pda, _) = Pubkey::find_program_address(&[b"owner_registry", owner.pubkey().as_ref()], &program_id);
+    let (second_agent, _) = Pubkey::find_program_address(&[b"agent", owner.pubkey().as_ref(), &1u64.to_le_bytes()], &program_id);
+    let (second_vault, _) = Pubkey::find_program_address(&[b"agent_vault", second_agent.as_ref()], &program_id);
+    let (second_metadata, _) = Pubkey::find_program_address(&[b"agent_metadata", second_agent.as_ref()], &program_id);
+
+    let mut reg_data = Vec::from(anchor_discriminator("register_agent"));
+    reg_data.extend(
+        RegisterAgentArgs {
+            name: "Sarah's Research".to_string(),
+            skill_uri: "ipfs://research".to_string(),
+            price_lamports: 20_000_000,
+            metadata: AgentMetadataArgs::default(),
+        }
+        .try_to_vec()?,
+    );
+    let reg_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(owner_registry_pda, false),
+            AccountMeta::new(second_agent, false),
+            AccountMeta::new(second_metadata, false),
+            AccountMeta::new(second_vault, false),
+            AccountMeta::new_readonly(platform_pda, false),
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: reg_data,
+    };
+    send_ix(&mut svm, reg_ix, &owner)?;
+
+    let registry_data = svm.get_account(&owner_registry_pda).expect("registry").data;
+    let registry = arcadium::OwnerRegistry::try_deserialize(&mut registry_data.as_slice())?;
+    assert_eq!(registry.agents_created, 2);
+    assert_eq!(registry.agent_count, 2);
+
+    let agent_data = svm.get_account(&second_agent).expect("second agent").data;
+    let agent = arcadium::Agent::try_deserialize(&mut agent_data.as_slice())?;
+    assert_eq!(agent.agent_id, 1);
+    assert_eq!(agent.owner.to_bytes(), owner.pubkey().to_bytes());
+    assert!(svm.get_account(&first_agent).is_some());
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_agent_ownership_two_step_handoff() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, _, _) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let buyer = Keypair::new();
+    svm.airdrop(&buyer.pubkey(), 1_000_000_000)?;
+
+    let mut propose_data = Vec::from(anchor_discriminator("propose_agent_owner"));
+    propose_data.extend(ProposeAgentOwnerArgs { new_owner: buyer.pubkey().to_bytes() }.try_to_vec()?);
+    let propose_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new_readonly(owner.pubkey(), true),
+        ],
+        data: propose_data,
+    };
+    send_ix(&mut svm, propose_ix, &owner)?;
+
+    let accept_ix = |signer: &Keypair| Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new_readonly(signer.pubkey(), true),
+        ],
+        data: Vec::from(anchor_discriminator("accept_agent_owner")),
+    };
+    // Only the proposed wallet can accept.
+    assert!(send_ix(&mut svm, accept_ix(&client), &client).is_err());
+    send_ix(&mut svm, accept_ix(&buyer), &buyer)?;
+
+    // The agent PDA is unchanged; owner-gated instructions follow the new owner.
+    let update_ix = |signer: &Keypair| {
+        let mut data = Vec::from(anchor_discriminator("update_agent"));
+        data.extend(
+            UpdateAgentArgs {
+                name: None,
+                skill_uri: None,
+                price_lamports: None,
+                is_active: Some(false),
+            }
+            .try_to_vec()
+            .unwrap(),
+        );
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(agent_pda, false),
+                AccountMeta::new_readonly(signer.pubkey(), true),
+            ],
+            data,
+        }
+    };
+    assert!(send_ix(&mut svm, update_ix(&owner), &owner).is_err());
+    send_ix(&mut svm, update_ix(&buyer), &buyer)?;
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_operator_submits_deliverable_but_cannot_withdraw() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, job_pda, _) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    let (agent_vault_pda, _) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
+    let operator = Keypair::new();
+    svm.airdrop(&operator.pubkey(), 1_000_000_000)?;
+
+    let mut set_data = Vec::from(anchor_discriminator("set_agent_operator"));
+    set_data.extend(SetAgentOperatorArgs { operator: Some(operator.pubkey().to_bytes()) }.try_to_vec()?);
+    let set_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new_readonly(owner.pubkey(), true),
+        ],
+        data: set_data,
+    };
+    // Only the owner can appoint an operator.
+    let mut forged = set_ix.clone();
+    forged.accounts[1] = AccountMeta::new_readonly(operator.pubkey(), true);
+    assert!(send_ix(&mut svm, forged, &operator).is_err());
+    send_ix(&mut svm, set_ix, &owner)?;
+
+    let mut deliver_data = Vec::from(anchor_discriminator("submit_deliverable"));
+    deliver_data.extend(
+        SubmitDeliverableArgs {
+            content_hash: [7u8; 32],
+            uri: "ipfs://deliverable".to_string(),
+        }
+        .try_to_vec()?,
+    );
+    let deliver_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new_readonly(agent_pda, false),
+            AccountMeta::new_readonly(platform_pda, false),
+            AccountMeta::new_readonly(operator.pubkey(), true),
+        ],
+        data: deliver_data,
+    };
+    send_ix(&mut svm, deliver_ix, &operator)?;
+
+    let mut withdraw_data = Vec::from(anchor_discriminator("withdraw_earnings"));
+    withdraw_data.extend(1u64.to_le_bytes());
+    let withdraw_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(agent_pda, false),
+            AccountMeta::new(agent_vault_pda, false),
+            AccountMeta::new(operator.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: withdraw_data,
+    };
+    assert!(send_ix(&mut svm, withdraw_ix, &operator).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_withdraw_platform_fees_from_treasury() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, job_pda, escrow_pda) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    let (treasury_pda, _) = Pubkey::find_program_address(&[b"treasury"], &program_id);
+    let (agent_vault_pda, _) = Pubkey::find_program_address(&[b"agent_vault", agent_pda.as_ref()], &program_id);
+
+    let mut deliver_data = Vec::from(anchor_discriminator("submit_deliverable"));
+    deliver_data.extend(
+        SubmitDeliverableArgs {
+            content_hash: [7u8; 32],
+            uri: "ipfs://deliverable".to_string(),
+        }
+        .try_to_vec()?,
+    );
+    let deliver_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new_readonly(agent_pda, false),
+            AccountMeta::new_readonly(platform_pda, false),
+            AccountMeta::new_readonly(owner.pubkey(), true),
+        ],
+        data: deliver_data,
+    };
+    send_ix(&mut svm, deliver_ix, &owner)?;
+
+    let treasury_before = svm.get_balance(&treasury_pda).unwrap_or(0);
+    let complete_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(agent_vault_pda, false),
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(treasury_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: Vec::from(anchor_discriminator("complete_job")),
+    };
+    send_ix(&mut svm, complete_ix, &client)?;
+    assert_eq!(svm.get_balance(&treasury_pda).unwrap_or(0), treasury_before + 1_000_000);
+
+    // Fees go to a recipient of the authority's choosing.
+    let recipient = Pubkey::new_unique();
+    let withdraw_ix = |signer: Pubkey, amount: u64| {
+        let mut data = Vec::from(anchor_discriminator("withdraw_platform_fees"));
+        data.extend(amount.to_le_bytes());
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(platform_pda, false),
+                AccountMeta::new(treasury_pda, false),
+                AccountMeta::new_readonly(signer, true),
+                AccountMeta::new(recipient, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data,
+        }
+    };
+    assert!(send_ix(&mut svm, withdraw_ix(client.pubkey(), 100_000), &client).is_err());
+
+    // The treasury keeps its rent-exempt minimum.
+    let available = svm.get_balance(&treasury_pda).unwrap_or(0) - svm.minimum_balance_for_rent_exemption(0);
+    assert!(send_ix(&mut svm, withdraw_ix(authority.pubkey(), available + 1), &authority).is_err());
+    send_ix(&mut svm, withdraw_ix(authority.pubkey(), available), &authority)?;
+    assert_eq!(svm.get_balance(&recipient).unwrap_or(0), available);
+    assert_eq!(svm.get_balance(&treasury_pda).unwrap_or(0), svm.minimum_balance_for_rent_exemption(0));
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_platform_authority_two_step_handoff() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+    // Stand-in for a multisig vault; it only ever signs, never pays.
+    let new_authority = Keypair::new();
+
+    let mut propose_data = Vec::from(anchor_discriminator("propose_authority"));
+    propose_data.extend(ProposeAuthorityArgs { new_authority: new_authority.pubkey().to_bytes() }.try_to_vec()?);
+    let propose_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new_readonly(authority.pubkey(), true),
+        ],
+        data: propose_data,
+    };
+    send_ix(&mut svm, propose_ix, &authority)?;
+
+    let accept_ix = |signer: Pubkey| Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new_readonly(signer, true),
+        ],
+        data: Vec::from(anchor_discriminator("accept_authority")),
+    };
+    assert!(send_ix(&mut svm, accept_ix(client.pubkey()), &client).is_err());
+
+    // The fee payer is a separate wallet from the new authority.
+    let tx = Transaction::new_signed_with_payer(
+        &[accept_ix(new_authority.pubkey())],
+        Some(&client.pubkey()),
+        &[&client, &new_authority],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).map_err(|e| anyhow::anyhow!("accept_authority: {e:?}"))?;
+
+    let update_ix = |signer: Pubkey| {
+        let mut data = Vec::from(anchor_discriminator("update_platform_config"));
+        data.extend(
+            UpdatePlatformConfigArgs {
+                platform_fee_bps: Some(500),
+                review_window_secs: None,
+            }
+            .try_to_vec()
+            .unwrap(),
+        );
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(platform_pda, false),
+                AccountMeta::new_readonly(signer, true),
+            ],
+            data,
+        }
+    };
+    // The old authority is out.
+    assert!(send_ix(&mut svm, update_ix(authority.pubkey()), &authority).is_err());
+
+    let tx = Transaction::new_signed_with_payer(
+        &[update_ix(new_authority.pubkey())],
+        Some(&client.pubkey()),
+        &[&client, &new_authority],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).map_err(|e| anyhow::anyhow!("update_platform_config: {e:?}"))?;
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_fee_increase_is_timelocked_and_snapshotted() -> Result<()> {
+    use anchor_lang::AccountDeserialize;
+    use solana_sdk::clock::Clock;
+
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, first_job, _) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+
+    let update_ix = |platform_fee_bps: u16| {
+        let mut data = Vec::from(anchor_discriminator("update_platform_config"));
+        data.extend(
+            UpdatePlatformConfigArgs {
+                platform_fee_bps: Some(platform_fee_bps),
+                review_window_secs: None,
+            }
+            .try_to_vec()
+            .unwrap(),
+        );
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(platform_pda, false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+            ],
+            data,
+        }
+    };
+    // 10% -> 15% is more than one step allows.
+    assert!(send_ix(&mut svm, update_ix(1500), &authority).is_err());
+    send_ix(&mut svm, update_ix(1200), &authority)?;
+
+    let create_ix = |job_index: u64| {
+        let (job_pda, _) = Pubkey::find_program_address(&[b"job", agent_pda.as_ref(), &job_index.to_le_bytes()], &program_id);
+        let (escrow_pda, _) = Pubkey::find_program_address(&[b"escrow", job_pda.as_ref()], &program_id);
+        let mut data = Vec::from(anchor_discriminator("create_job"));
+        data.extend(
+            CreateJobArgs {
+                task_description: "Follow-up email".to_string(),
+                deadline: None,
+            }
+            .try_to_vec()
+            .unwrap(),
+        );
+        let ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(agent_pda, false),
+                AccountMeta::new(job_pda, false),
+                AccountMeta::new(escrow_pda, false),
+                AccountMeta::new(platform_pda, false),
+                AccountMeta::new(client.pubkey(), true),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data,
+        };
+        (ix, job_pda)
+    };
+    let job_fee_bps = |svm: &litesvm::LiteSVM, job_pda: Pubkey| -> Result<u16> {
+        let data = svm.get_account(&job_pda).expect("job").data;
+        Ok(arcadium::Job::try_deserialize(&mut data.as_slice())?.platform_fee_bps)
+    };
+
+    // Still queued: new jobs get the old fee.
+    let (ix, second_job) = create_ix(1);
+    send_ix(&mut svm, ix, &client)?;
+    assert_eq!(job_fee_bps(&svm, second_job)?, 1000);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp += arcadium::FEE_CHANGE_DELAY_SECS;
+    svm.set_sysvar(&clock);
+
+    let (ix, third_job) = create_ix(2);
+    send_ix(&mut svm, ix, &client)?;
+    assert_eq!(job_fee_bps(&svm, third_job)?, 1200);
+    assert_eq!(job_fee_bps(&svm, first_job)?, 1000);
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_pause_blocks_new_jobs_but_not_refunds() -> Result<()> {
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, job_pda, escrow_pda) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let (platform_pda, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+
+    let pause_ix = |paused: bool| {
+        let mut data = Vec::from(anchor_discriminator("set_paused"));
+        data.push(paused as u8);
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(platform_pda, false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+            ],
+            data,
+        }
+    };
+    send_ix(&mut svm, pause_ix(true), &authority)?;
+
+    let (second_job, _) = Pubkey::find_program_address(&[b"job", agent_pda.as_ref(), &1u64.to_le_bytes()], &program_id);
+    let (second_escrow, _) = Pubkey::find_program_address(&[b"escrow", second_job.as_ref()], &program_id);
+    let mut create_data = Vec::from(anchor_discriminator("create_job"));
+    create_data.extend(
+        CreateJobArgs {
+            task_description: "Follow-up email".to_string(),
+            deadline: None,
+        }
+        .try_to_vec()?,
+    );
+    let create_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(second_job, false),
+            AccountMeta::new(second_escrow, false),
+            AccountMeta::new(platform_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: create_data,
+    };
+    assert!(send_ix(&mut svm, create_ix.clone(), &client).is_err());
+
+    // The open job can still be cancelled and refunded.
+    let cancel_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: Vec::from(anchor_discriminator("cancel_job")),
+    };
+    send_ix(&mut svm, cancel_ix, &client)?;
+    assert_eq!(svm.get_balance(&escrow_pda).unwrap_or(0), 0);
+
+    send_ix(&mut svm, pause_ix(false), &authority)?;
+    svm.expire_blockhash();
+    send_ix(&mut svm, create_ix, &client)?;
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_cancel_job_emits_event() -> Result<()> {
+    use anchor_lang::{AnchorDeserialize, Discriminator};
+    use base64::Engine;
+
+    let program_id = arcadium::ID;
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let (agent_pda, job_pda, escrow_pda) = setup_open_job(&mut svm, &authority, &owner, &client, None)?;
+    let escrow_before = svm.get_balance(&escrow_pda).unwrap_or(0);
+
+    let cancel_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(job_pda, false),
+            AccountMeta::new(agent_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(client.pubkey(), true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: Vec::from(anchor_discriminator("cancel_job")),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[cancel_ix],
+        Some(&client.pubkey()),
+        &[&client],
+        svm.latest_blockhash(),
+    );
+    let meta = svm.send_transaction(tx).map_err(|e| anyhow::anyhow!("cancel_job: {e:?}"))?;
+
+    // `emit!` logs "Program data: <base64(discriminator ++ borsh(event))>".
+    let event = meta
+        .logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+        .find(|data| data.starts_with(arcadium::JobCancelled::DISCRIMINATOR))
+        .expect("JobCancelled event");
+    let event = arcadium::JobCancelled::try_from_slice(&event[8..])?;
+    assert_eq!(event.job.to_bytes(), job_pda.to_bytes());
+    assert_eq!(event.client.to_bytes(), client.pubkey().to_bytes());
+    assert_eq!(event.refunded_amount, escrow_before);
+
+    Ok(())
+}
+
+/// Send `ix` and decode the first `E` event from its logs.
+fn send_for_event<E: anchor_lang::AnchorDeserialize + anchor_lang::Discriminator>(
+    svm: &mut litesvm::LiteSVM,
+    ix: Instruction,
+    signer: &Keypair,
+) -> Result<E> {
+    use base64::Engine;
+
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&signer.pubkey()), &[signer], svm.latest_blockhash());
+    let meta = svm.send_transaction(tx).map_err(|e| anyhow::anyhow!("transaction failed: {e:?}"))?;
+    let data = meta
+        .logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+        .find(|data| data.starts_with(E::DISCRIMINATOR))
+        .ok_or_else(|| anyhow::anyhow!("event not emitted"))?;
+    Ok(E::try_from_slice(&data[E::DISCRIMINATOR.len()..])?)
+}
+
+#[test]
+fn litesvm_dispute_resolution_emits_payout_split() -> Result<()> {
+    use anchor_lang::prelude::Pubkey as AnchorPubkey;
+    use arcadium_client::{instructions as ix, pda};
+
+    let key = |keypair: &Keypair| AnchorPubkey::new_from_array(keypair.pubkey().to_bytes());
+    let sdk_key = |pubkey: AnchorPubkey| Pubkey::new_from_array(pubkey.to_bytes());
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    send_ix(&mut svm, sdk_ix(ix::initialize(key(&authority), 1000, 86_400)), &authority)?;
+    send_ix(
+        &mut svm,
+        sdk_ix(ix::register_agent(key(&owner), 0, "Sarah".to_string(), "ipfs://example".to_string(), 10_000_000, Default::default(), None)),
+        &owner,
+    )?;
+    let agent = pda::agent(&key(&owner), 0).0;
+    let job = pda::job(&agent, 0).0;
+    send_ix(&mut svm, sdk_ix(ix::create_job(key(&client), agent, 0, "Task".to_string(), None, None)), &client)?;
+    send_ix(&mut svm, sdk_ix(ix::dispute_job(key(&client), job)), &client)?;
+    let escrow_before = svm.get_balance(&sdk_key(pda::escrow(&job).0)).unwrap_or(0);
+
+    // 70% of the price to the agent, less the 10% fee; the rest (and the
+    // escrow's rent) back to the client
+    let event: arcadium::DisputeResolved = send_for_event(
+        &mut svm,
+        sdk_ix(ix::resolve_dispute(key(&authority), key(&client), agent, job, 7000, None)),
+        &authority,
+    )?;
+    assert_eq!(event.job, job);
+    assert_eq!(event.agent_share_bps, 7000);
+    assert_eq!(event.agent_amount, 6_300_000);
+    assert_eq!(event.platform_fee, 700_000);
+    assert_eq!(event.client_amount, escrow_before - 7_000_000);
+    assert_eq!(event.mint, None);
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_initialize_and_handoff_proposals_emit_events() -> Result<()> {
+    use anchor_lang::prelude::Pubkey as AnchorPubkey;
+    use arcadium_client::{instructions as ix, pda};
+
+    let key = |keypair: &Keypair| AnchorPubkey::new_from_array(keypair.pubkey().to_bytes());
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let event: arcadium::PlatformInitialized =
+        send_for_event(&mut svm, sdk_ix(ix::initialize(key(&authority), 1000, 86_400)), &authority)?;
+    assert_eq!(event.authority, key(&authority));
+    assert_eq!(event.platform_fee_bps, 1000);
+    assert_eq!(event.review_window_secs, 86_400);
+    send_ix(
+        &mut svm,
+        sdk_ix(ix::register_agent(key(&owner), 0, "Sarah".to_string(), "ipfs://example".to_string(), 10_000_000, Default::default(), None)),
+        &owner,
+    )?;
+    let agent = pda::agent(&key(&owner), 0).0;
+
+    let event: arcadium::AuthorityProposed =
+        send_for_event(&mut svm, sdk_ix(ix::propose_authority(key(&authority), key(&client))), &authority)?;
+    assert_eq!(event.authority, key(&authority));
+    assert_eq!(event.pending_authority, key(&client));
+
+    let event: arcadium::AgentOwnerProposed =
+        send_for_event(&mut svm, sdk_ix(ix::propose_agent_owner(key(&owner), agent, key(&client))), &owner)?;
+    assert_eq!(event.agent, agent);
+    assert_eq!(event.owner, key(&owner));
+    assert_eq!(event.pending_owner, key(&client));
+
+    Ok(())
+}
+
+/// Convert an instruction built by `arcadium-client` (Anchor's Solana types)
+/// into the SDK types LiteSVM takes.
+fn sdk_ix(ix: anchor_lang::solana_program::instruction::Instruction) -> Instruction {
+    Instruction {
+        program_id: Pubkey::new_from_array(ix.program_id.to_bytes()),
+        accounts: ix
+            .accounts
+            .into_iter()
+            .map(|meta| AccountMeta {
+                pubkey: Pubkey::new_from_array(meta.pubkey.to_bytes()),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: ix.data,
+    }
+}
+
+#[test]
+fn litesvm_client_builders_drive_job_lifecycle() -> Result<()> {
+    use anchor_lang::prelude::Pubkey as AnchorPubkey;
+    use arcadium_client::{instructions as ix, pda};
+
+    let key = |keypair: &Keypair| AnchorPubkey::new_from_array(keypair.pubkey().to_bytes());
+    let sdk_key = |pubkey: AnchorPubkey| Pubkey::new_from_array(pubkey.to_bytes());
+
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let agent = pda::agent(&key(&owner), 0).0;
+    let job = pda::job(&agent, 0).0;
+
+    send_ix(&mut svm, sdk_ix(ix::initialize(key(&authority), 1000, 86_400)), &authority)?;
+    send_ix(
+        &mut svm,
+        sdk_ix(ix::register_agent(key(&owner), 0, "Sarah".to_string(), "ipfs://example".to_string(), 10_000_000, Default::default(), None)),
+        &owner,
+    )?;
+    send_ix(
+        &mut svm,
+        sdk_ix(ix::create_job(key(&client), agent, 0, "Decline meeting".to_string(), None, None)),
+        &client,
+    )?;
+    send_ix(
+        &mut svm,
+        sdk_ix(ix::submit_deliverable(key(&owner), agent, job, [7u8; 32], "ipfs://deliverable".to_string())),
+        &owner,
+    )?;
+    send_ix(&mut svm, sdk_ix(ix::complete_job(key(&client), agent, job, None)), &client)?;
+    send_ix(
+        &mut svm,
+        sdk_ix(ix::rate_job(key(&client), agent, job, 5, "Perfect output".to_string())),
+        &client,
+    )?;
+
+    let job_account = arcadium_client::decode_job(&svm.get_account(&sdk_key(job)).expect("job").data)?;
+    assert!(job_account.status == arcadium_client::JobStatus::Completed);
+    assert!(job_account.rated);
+
+    send_ix(&mut svm, sdk_ix(ix::close_job(key(&client), job, None)), &client)?;
+    assert!(svm.get_account(&sdk_key(job)).is_none());
+
+    let agent_account = arcadium_client::decode_agent(&svm.get_account(&sdk_key(agent)).expect("agent").data)?;
+    assert_eq!(agent_account.jobs_completed, 1);
+    assert_eq!(agent_account.rating_sum, 5);
+    assert_eq!(agent_account.open_jobs, 0);
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_agent_metadata_resizes_and_settles_rent() -> Result<()> {
+    use anchor_lang::prelude::Pubkey as AnchorPubkey;
+    use arcadium_client::{instructions as ix, pda, AgentMetadataInput};
+
+    let key = |keypair: &Keypair| AnchorPubkey::new_from_array(keypair.pubkey().to_bytes());
+    let sdk_key = |pubkey: AnchorPubkey| Pubkey::new_from_array(pubkey.to_bytes());
+
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let agent = pda::agent(&key(&owner), 0).0;
+    let metadata_pda = sdk_key(pda::agent_metadata(&agent).0);
+
+    let initial = AgentMetadataInput {
+        endpoint_url: "https://sarah.example/webhook".to_string(),
+        description: "Professional emails".to_string(),
+        tags: vec!["email".to_string()],
+        categories: Vec::new(),
+        encryption_key: None,
+    };
+    send_ix(&mut svm, sdk_ix(ix::initialize(key(&authority), 1000, 86_400)), &authority)?;
+    send_ix(
+        &mut svm,
+        sdk_ix(ix::register_agent(key(&owner), 0, "Sarah".to_string(), "ipfs://example".to_string(), 10_000_000, initial.clone(), None)),
+        &owner,
+    )?;
+    let account = svm.get_account(&metadata_pda).expect("metadata");
+    assert_eq!(account.data.len(), arcadium::AgentMetadata::space(&initial));
+
+    // Longer fields: the account grows, paid by the owner
+    let grown = AgentMetadataInput {
+        endpoint_url: "https://agents.sarah.example/v2/webhook".to_string(),
+        description: "Professional emails, follow-ups and meeting declines".to_string(),
+        tags: vec!["email".to_string(), "writing".to_string(), "business".to_string()],
+        categories: Vec::new(),
+        encryption_key: Some([9u8; 32]),
+    };
+    send_ix(&mut svm, sdk_ix(ix::update_agent_metadata(key(&owner), agent, &[], grown.clone())), &owner)?;
+    let account = svm.get_account(&metadata_pda).expect("metadata");
+    assert_eq!(account.data.len(), arcadium::AgentMetadata::space(&grown));
+    assert_eq!(account.lamports, svm.minimum_balance_for_rent_exemption(account.data.len()));
+    let stored = arcadium_client::decode_agent_metadata(&account.data)?;
+    assert_eq!(stored.agent, agent);
+    assert_eq!(stored.endpoint_url, grown.endpoint_url);
+    assert_eq!(stored.tags, grown.tags);
+    assert_eq!(stored.encryption_key, Some([9u8; 32]));
+
+    // Shorter fields: the account shrinks and the owner gets the rent back
+    let owner_before = svm.get_balance(&owner.pubkey()).unwrap_or(0);
+    let refund = account.lamports - svm.minimum_balance_for_rent_exemption(arcadium::AgentMetadata::space(&initial));
+    send_ix(&mut svm, sdk_ix(ix::update_agent_metadata(key(&owner), agent, &[], initial.clone())), &owner)?;
+    let account = svm.get_account(&metadata_pda).expect("metadata");
+    assert_eq!(account.data.len(), arcadium::AgentMetadata::space(&initial));
+    assert_eq!(svm.get_balance(&owner.pubkey()).unwrap_or(0), owner_before + refund - 5000);
+
+    // Only the owner may edit, and endpoints must be https
+    let res = send_ix(&mut svm, sdk_ix(ix::update_agent_metadata(key(&client), agent, &[], initial.clone())), &client);
+    assert!(res.is_err(), "non-owner should not update metadata");
+    for endpoint_url in ["ftp://sarah.example", "http://sarah.example/webhook"] {
+        let bad = AgentMetadataInput {
+            endpoint_url: endpoint_url.to_string(),
+            ..initial.clone()
+        };
+        let res = send_ix(&mut svm, sdk_ix(ix::update_agent_metadata(key(&owner), agent, &[], bad)), &owner);
+        assert!(res.is_err(), "{endpoint_url} should be rejected");
+    }
+
+    // Closing the agent closes its metadata too
+    send_ix(&mut svm, sdk_ix(ix::close_agent(key(&owner), key(&owner), agent, &[], None)), &owner)?;
+    assert!(svm.get_account(&metadata_pda).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_categories_count_their_agents() -> Result<()> {
+    use anchor_lang::prelude::Pubkey as AnchorPubkey;
+    use arcadium_client::{instructions as ix, pda, AgentMetadataInput};
+
+    let key = |keypair: &Keypair| AnchorPubkey::new_from_array(keypair.pubkey().to_bytes());
+    let sdk_key = |pubkey: AnchorPubkey| Pubkey::new_from_array(pubkey.to_bytes());
+    let agent_count = |svm: &litesvm::LiteSVM, category_id: u16| -> Result<u32> {
+        let account = svm.get_account(&sdk_key(pda::category(category_id).0)).expect("category");
+        Ok(arcadium_client::decode_category(&account.data)?.agent_count)
+    };
+
+    let (mut svm, authority, owner, client) = setup_svm()?;
+    let agent = pda::agent(&key(&owner), 0).0;
+    send_ix(&mut svm, sdk_ix(ix::initialize(key(&authority), 1000, 86_400)), &authority)?;
+
+    // Only the authority curates categories
+    let res = send_ix(&mut svm, sdk_ix(ix::create_category(key(&client), key(&client), 1, "Email writing".to_string())), &client);
+    assert!(res.is_err(), "non-authority should not create categories");
+    for (category_id, name) in [(1, "Email writing"), (2, "Scheduling"), (3, "Research")] {
+        send_ix(&mut svm, sdk_ix(ix::create_category(key(&authority), key(&authority), category_id, name.to_string())), &authority)?;
+    }
+    send_ix(&mut svm, sdk_ix(ix::update_category(key(&authority), 3, None, Some(false))), &authority)?;
+
+    let register = |categories: Vec<u16>| {
+        let metadata = AgentMetadataInput {
+            categories,
+            ..Default::default()
+        };
+        ix::register_agent(key(&owner), 0, "Sarah".to_string(), "ipfs://example".to_string(), 10_000_000, metadata, None)
+    };
+
+    // Category accounts must be passed, and inactive ones take no agents
+    let mut bare = register(vec![1]);
+    bare.accounts.pop();
+    assert!(send_ix(&mut svm, sdk_ix(bare), &owner).is_err(), "missing category account should fail");
+    assert!(send_ix(&mut svm, sdk_ix(register(vec![1, 3])), &owner).is_err(), "inactive category should be refused");
+    assert!(send_ix(&mut svm, sdk_ix(register(vec![1, 1])), &owner).is_err(), "duplicate category should be refused");
+
+    send_ix(&mut svm, sdk_ix(register(vec![1, 2])), &owner)?;
+    assert_eq!(agent_count(&svm, 1)?, 1);
+    assert_eq!(agent_count(&svm, 2)?, 1);
+
+    // Moving from {1, 2} to {2}: only category 1 changes
+    let metadata = AgentMetadataInput {
+        categories: vec![2],
+        ..Default::default()
+    };
+    send_ix(&mut svm, sdk_ix(ix::update_agent_metadata(key(&owner), agent, &[1, 2], metadata)), &owner)?;
+    assert_eq!(agent_count(&svm, 1)?, 0);
+    assert_eq!(agent_count(&svm, 2)?, 1);
+
+    // Deactivating keeps existing agents listed; closing the agent releases them
+    send_ix(&mut svm, sdk_ix(ix::update_category(key(&authority), 2, Some("Calendar".to_string()), Some(false))), &authority)?;
+    assert_eq!(agent_count(&svm, 2)?, 1);
+    send_ix(&mut svm, sdk_ix(ix::close_agent(key(&owner), key(&owner), agent, &[2], None)), &owner)?;
+    assert_eq!(agent_count(&svm, 2)?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn litesvm_raw_register_with_categories_pads_optional_accounts() -> Result<()> {
+    use anchor_lang::prelude::Pubkey as AnchorPubkey;
+    use arcadium_client::{instructions as ix, pda};
+
+    let key = |keypair: &Keypair| AnchorPubkey::new_from_array(keypair.pubkey().to_bytes());
+    let sdk_key = |pubkey: AnchorPubkey| Pubkey::new_from_array(pubkey.to_bytes());
+    let program_id = Pubkey::new_from_array(arcadium::ID.to_bytes());
+
+    let (mut svm, authority, owner, _client) = setup_svm()?;
+    send_ix(&mut svm, sdk_ix(ix::initialize(key(&authority), 1000, 86_400)), &authority)?;
+    send_ix(&mut svm, sdk_ix(ix::create_category(key(&authority), key(&authority), 1, "Email writing".to_string())), &authority)?;
+
+    // register_agent built by hand rather than with `arcadium-client`
+    let agent = pda::agent(&key(&owner), 0).0;
+    let category = sdk_key(pda::category(1).0);
+    let mut data = Vec::from(anchor_discriminator("register_agent"));
+    data.extend(borsh::to_vec(&RegisterAgentArgs {
+        name: "Sarah".to_string(),
+        skill_uri: "ipfs://example".to_string(),
+        price_lamports: 10_000_000,
+        metadata: AgentMetadataArgs {
+            categories: vec![1],
+            ..Default::default()
+        },
+    })?);
+    let required = vec![
+        AccountMeta::new(sdk_key(pda::owner_registry(&key(&owner)).0), false),
+        AccountMeta::new(sdk_key(agent), false),
+        AccountMeta::new(sdk_key(pda::agent_metadata(&agent).0), false),
+        AccountMeta::new(sdk_key(pda::agent_vault(&agent).0), false),
+        AccountMeta::new_readonly(sdk_key(pda::platform().0), false),
+        AccountMeta::new(owner.pubkey(), true),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let register = |optional: Vec<AccountMeta>| Instruction {
+        program_id,
+        accounts: [required.clone(), optional, vec![AccountMeta::new(category, false)]].concat(),
+        data: data.clone(),
+    };
+
+    // Omitting the optional token accounts puts the category in the `mint` slot
+    assert!(send_ix(&mut svm, register(Vec::new()), &owner).is_err(), "unpadded categories should fail");
+
+    // The program ID stands in for each of the six unused token accounts
+    send_ix(&mut svm, register(vec![AccountMeta::new_readonly(program_id, false); 6]), &owner)?;
+    let account = svm.get_account(&category).expect("category");
+    assert_eq!(arcadium_client::decode_category(&account.data)?.agent_count, 1);
 
     Ok(())
 }
@@ -0,0 +1,439 @@
+//! Job-dispatch relay: POST each new job to its agent's webhook and record
+//! the result for the agent to commit on-chain with `submit_deliverable`.
+//!
+//! [`Relay::poll`] takes the open jobs from a [`JobSource`], skips any it
+//! has already delivered, and dispatches the rest to the agent's webhook:
+//! the one in the config if set, otherwise the `endpoint_url` from its
+//! on-chain metadata. Anyone can publish the latter, so it must be `https`
+//! and may only reach public addresses (see [`is_public`]).
+//!
+//! A webhook call is retried with exponential backoff on transport errors
+//! and 5xx responses; 4xx responses and bodies over [`MAX_BODY`] bytes
+//! fail immediately. Every finished dispatch is stored in SQLite, so
+//! restarts never resend a delivered job; failed ones are tried again after
+//! `retry_failed_secs`.
+
+pub mod source;
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use arcadium_client::Job;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub use source::{JobSource, RpcSource};
+
+/// Largest webhook response the relay reads; a longer one fails the dispatch.
+pub const MAX_BODY: u64 = 1 << 20;
+
+/// Relay settings, read from a JSON file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Per-request timeout
+    pub timeout_secs: u64,
+    /// Total tries per job, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled on each later one
+    pub retry_backoff_ms: u64,
+    /// How long a failed dispatch waits before a later poll sends it again
+    pub retry_failed_secs: u64,
+    /// Webhook URL per agent address, overriding on-chain metadata. Unlike
+    /// on-chain endpoints these may point at private addresses.
+    pub endpoints: HashMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            max_attempts: 3,
+            retry_backoff_ms: 1_000,
+            retry_failed_secs: 300,
+            endpoints: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    fn endpoint(&self, agent: &Pubkey) -> Option<&str> {
+        self.endpoints.get(&agent.to_string()).map(String::as_str)
+    }
+}
+
+/// Body POSTed to an agent's webhook.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct JobRequest {
+    pub job: String,
+    pub agent: String,
+    pub job_id: u64,
+    pub client: String,
+    pub task_description: String,
+    pub price: u64,
+    pub mint: Option<String>,
+    pub deadline: Option<i64>,
+    pub created_at: i64,
+}
+
+impl JobRequest {
+    pub fn new(address: &Pubkey, job: &Job) -> Self {
+        Self {
+            job: address.to_string(),
+            agent: job.agent.to_string(),
+            job_id: job.job_id,
+            client: job.client.to_string(),
+            task_description: job.task_description.clone(),
+            price: job.price_lamports,
+            mint: job.mint.map(|mint| mint.to_string()),
+            deadline: job.deadline,
+            created_at: job.created_at,
+        }
+    }
+}
+
+/// What one [`Relay::poll`] did.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PollResult {
+    /// Results of the jobs dispatched this time
+    pub dispatched: Vec<Dispatch>,
+    /// Jobs left for a later poll, e.g. because the agent has no endpoint
+    pub skipped: Vec<Skipped>,
+}
+
+/// An open job [`Relay::poll`] could not dispatch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Skipped {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub reason: String,
+}
+
+/// A stored dispatch result.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dispatch {
+    pub job: Pubkey,
+    pub agent: Pubkey,
+    pub job_id: u64,
+    pub endpoint: String,
+    pub delivered: bool,
+    pub attempts: u32,
+    pub http_status: Option<u16>,
+    pub response: Option<Vec<u8>>,
+    /// SHA-256 of `response`, the `content_hash` to submit on-chain.
+    pub content_hash: Option<[u8; 32]>,
+    /// `deliverable_uri` from a JSON response body, if the agent sent one.
+    pub deliverable_uri: Option<String>,
+    pub error: Option<String>,
+    pub dispatched_at: i64,
+    pub submitted: bool,
+}
+
+/// Where [`Relay::poll`] sends an agent's jobs.
+enum Route {
+    /// From the config, trusted to reach any address
+    Configured(String),
+    /// Published on-chain, limited to public addresses
+    Published(String),
+    /// Nowhere yet, for the given reason
+    Unroutable(String),
+}
+
+pub struct Relay {
+    config: Config,
+    http: ureq::Agent,
+    /// Client for on-chain endpoints; refuses to connect to non-public addresses.
+    public_http: ureq::Agent,
+    db: Connection,
+}
+
+impl Relay {
+    /// Open (creating if needed) the result store at `db_path`.
+    pub fn open(config: Config, db_path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_connection(config, Connection::open(db_path)?)
+    }
+
+    pub fn open_in_memory(config: Config) -> Result<Self> {
+        Self::with_connection(config, Connection::open_in_memory()?)
+    }
+
+    fn with_connection(config: Config, db: Connection) -> Result<Self> {
+        db.execute_batch(include_str!("schema.sql"))?;
+        let timeout = Duration::from_secs(config.timeout_secs);
+        let http = ureq::AgentBuilder::new().timeout(timeout).build();
+        // Redirects stay on https and pass through the same resolver check.
+        let public_http = ureq::AgentBuilder::new()
+            .timeout(timeout)
+            .https_only(true)
+            .resolver(resolve_public)
+            .build();
+        Ok(Self { config, http, public_http, db })
+    }
+
+    /// Dispatch every open job not yet delivered, retrying failed ones once
+    /// `retry_failed_secs` have passed.
+    pub fn poll(&self, source: &dyn JobSource) -> Result<PollResult> {
+        let mut result = PollResult::default();
+        let retry_before = unix_now().saturating_sub(self.config.retry_failed_secs as i64);
+        for (address, job) in source.open_jobs()? {
+            match self.dispatch_record(&address)? {
+                Some(record) if record.delivered || record.dispatched_at > retry_before => continue,
+                _ => {}
+            }
+            let dispatch = match self.route(source, &job.agent)? {
+                Route::Configured(endpoint) => self.send(&self.http, &address, &job, &endpoint),
+                Route::Published(endpoint) => self.send(&self.public_http, &address, &job, &endpoint),
+                Route::Unroutable(reason) => {
+                    // Left unrecorded so it goes out once the agent has a usable endpoint.
+                    result.skipped.push(Skipped { job: address, agent: job.agent, reason });
+                    continue;
+                }
+            };
+            self.record(&dispatch)?;
+            result.dispatched.push(dispatch);
+        }
+        Ok(result)
+    }
+
+    /// The configured webhook for `agent`, else the one it published on-chain.
+    fn route(&self, source: &dyn JobSource, agent: &Pubkey) -> Result<Route> {
+        if let Some(endpoint) = self.config.endpoint(agent) {
+            return Ok(Route::Configured(endpoint.to_string()));
+        }
+        let published = source
+            .agent_metadata(agent)?
+            .map(|metadata| metadata.endpoint_url)
+            .unwrap_or_default();
+        Ok(if published.is_empty() {
+            Route::Unroutable("no webhook endpoint".to_string())
+        } else if !published.starts_with("https://") {
+            Route::Unroutable(format!("on-chain endpoint {published} is not https"))
+        } else {
+            Route::Published(published)
+        })
+    }
+
+    /// POST `job` to `endpoint`, retrying as configured. The endpoint is
+    /// trusted like a configured one.
+    pub fn dispatch(&self, address: &Pubkey, job: &Job, endpoint: &str) -> Dispatch {
+        self.send(&self.http, address, job, endpoint)
+    }
+
+    fn send(&self, http: &ureq::Agent, address: &Pubkey, job: &Job, endpoint: &str) -> Dispatch {
+        let body = serde_json::to_string(&JobRequest::new(address, job)).expect("JobRequest serializes");
+        let mut dispatch = Dispatch {
+            job: *address,
+            agent: job.agent,
+            job_id: job.job_id,
+            endpoint: endpoint.to_string(),
+            delivered: false,
+            attempts: 0,
+            http_status: None,
+            response: None,
+            content_hash: None,
+            deliverable_uri: None,
+            error: None,
+            dispatched_at: unix_now(),
+            submitted: false,
+        };
+
+        let max_attempts = self.config.max_attempts.max(1);
+        while dispatch.attempts < max_attempts {
+            if dispatch.attempts > 0 {
+                let backoff = self.config.retry_backoff_ms << (dispatch.attempts - 1).min(16);
+                std::thread::sleep(Duration::from_millis(backoff));
+            }
+            dispatch.attempts += 1;
+
+            let result = http
+                .post(endpoint)
+                .set("Content-Type", "application/json")
+                .send_string(&body);
+            match result {
+                Ok(response) => {
+                    dispatch.http_status = Some(response.status());
+                    let mut bytes = Vec::new();
+                    match response.into_reader().take(MAX_BODY + 1).read_to_end(&mut bytes) {
+                        Ok(_) if bytes.len() as u64 > MAX_BODY => {
+                            dispatch.error = Some(format!("oversized response: over {MAX_BODY} bytes"));
+                            break;
+                        }
+                        Ok(_) => {
+                            dispatch.content_hash = Some(Sha256::digest(&bytes).into());
+                            dispatch.deliverable_uri = deliverable_uri(&bytes);
+                            dispatch.response = Some(bytes);
+                            dispatch.delivered = true;
+                            dispatch.error = None;
+                            break;
+                        }
+                        Err(e) => dispatch.error = Some(format!("reading response: {e}")),
+                    }
+                }
+                Err(ureq::Error::Status(status, response)) => {
+                    dispatch.http_status = Some(status);
+                    dispatch.error = Some(format!("HTTP {status}: {}", response.into_string().unwrap_or_default()));
+                    if status < 500 {
+                        break;
+                    }
+                }
+                Err(e) => dispatch.error = Some(e.to_string()),
+            }
+        }
+        dispatch
+    }
+
+    /// The stored result for `job`, if it has been dispatched.
+    pub fn dispatch_record(&self, job: &Pubkey) -> Result<Option<Dispatch>> {
+        Ok(self
+            .db
+            .query_row("SELECT * FROM dispatches WHERE job = ?1", [job.to_string()], read_dispatch)
+            .optional()?)
+    }
+
+    /// Delivered results not yet marked submitted, oldest first.
+    pub fn pending_submissions(&self) -> Result<Vec<Dispatch>> {
+        let mut stmt = self.db.prepare(
+            "SELECT * FROM dispatches WHERE status = 'delivered' AND submitted = 0 ORDER BY dispatched_at",
+        )?;
+        let rows = stmt.query_map([], read_dispatch)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Note that the agent has committed `job`'s result on-chain.
+    pub fn mark_submitted(&self, job: &Pubkey) -> Result<()> {
+        self.db
+            .execute("UPDATE dispatches SET submitted = 1 WHERE job = ?1", [job.to_string()])?;
+        Ok(())
+    }
+
+    fn record(&self, dispatch: &Dispatch) -> Result<()> {
+        self.db.execute(
+            "INSERT OR REPLACE INTO dispatches (job, agent, job_id, endpoint, status, attempts,
+                http_status, response, content_hash, deliverable_uri, error, dispatched_at, submitted)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                dispatch.job.to_string(),
+                dispatch.agent.to_string(),
+                dispatch.job_id as i64,
+                dispatch.endpoint,
+                if dispatch.delivered { "delivered" } else { "failed" },
+                dispatch.attempts,
+                dispatch.http_status,
+                dispatch.response,
+                dispatch.content_hash.map(hex::encode),
+                dispatch.deliverable_uri,
+                dispatch.error,
+                dispatch.dispatched_at,
+                dispatch.submitted,
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+fn read_dispatch(row: &Row) -> rusqlite::Result<Dispatch> {
+    let key = |column: &str| -> rusqlite::Result<Pubkey> {
+        let text: String = row.get(column)?;
+        text.parse().map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })
+    };
+    let content_hash: Option<String> = row.get("content_hash")?;
+    let job_id: i64 = row.get("job_id")?;
+    let status: String = row.get("status")?;
+    Ok(Dispatch {
+        job: key("job")?,
+        agent: key("agent")?,
+        job_id: job_id as u64,
+        endpoint: row.get("endpoint")?,
+        delivered: status == "delivered",
+        attempts: row.get("attempts")?,
+        http_status: row.get("http_status")?,
+        response: row.get("response")?,
+        content_hash: content_hash.and_then(|hash| hex::decode(hash).ok()?.try_into().ok()),
+        deliverable_uri: row.get("deliverable_uri")?,
+        error: row.get("error")?,
+        dispatched_at: row.get("dispatched_at")?,
+        submitted: row.get("submitted")?,
+    })
+}
+
+/// Resolve `netloc` for an on-chain endpoint, refusing loopback, private,
+/// link-local and other non-public addresses. Checked at connect time, so
+/// redirects and DNS changes can't get around it.
+fn resolve_public(netloc: &str) -> io::Result<Vec<SocketAddr>> {
+    let addrs: Vec<SocketAddr> = netloc.to_socket_addrs()?.collect();
+    if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a public address", addr.ip()),
+        ));
+    }
+    Ok(addrs)
+}
+
+/// Whether `ip` is globally routable, i.e. one the relay may reach for an
+/// on-chain endpoint. IPv6 addresses that embed an IPv4 one are judged by
+/// that address.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            !(a == 0 // "This network", including 0.0.0.0
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || a >= 224 // Multicast, reserved and broadcast
+                || (a == 100 && (64..128).contains(&b)) // Carrier-grade NAT
+                || (a == 192 && b == 0 && c == 0) // IETF protocol assignments
+                || (a == 198 && (b == 18 || b == 19))) // Benchmarking
+        }
+        IpAddr::V6(ip) => match embedded_ipv4(ip) {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                !(first & 0xfe00 == 0xfc00 // Unique local
+                    || first & 0xffc0 == 0xfe80 // Link-local
+                    || first & 0xff00 == 0xff00) // Multicast
+            }
+        },
+    }
+}
+
+/// The IPv4 address carried by an IPv4-mapped (`::ffff:a.b.c.d`),
+/// IPv4-compatible (`::a.b.c.d`, which covers `::` and `::1`), NAT64
+/// (`64:ff9b::a.b.c.d`) or 6to4 (`2002:aabb:ccdd::`) address.
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let ipv4 = |high: u16, low: u16| Ipv4Addr::from((u32::from(high) << 16) | u32::from(low));
+    match ip.segments() {
+        [0, 0, 0, 0, 0, 0xffff, high, low]
+        | [0, 0, 0, 0, 0, 0, high, low]
+        | [0x64, 0xff9b, 0, 0, 0, 0, high, low]
+        | [0x2002, high, low, ..] => Some(ipv4(high, low)),
+        _ => None,
+    }
+}
+
+/// `deliverable_uri` from a JSON object response, if present.
+fn deliverable_uri(body: &[u8]) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_slice(body).ok()?;
+    Some(value.get("deliverable_uri")?.as_str()?.to_string())
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
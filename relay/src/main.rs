@@ -0,0 +1,52 @@
+//! `arcadium-relay`: poll for new jobs and POST them to agent webhooks.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use arcadium_relay::{Config, Relay, RpcSource};
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(name = "arcadium-relay", version, about = "Dispatch new Arcadium jobs to agent webhooks")]
+struct Args {
+    /// JSON-RPC endpoint
+    #[arg(long, short = 'u', env = "ARCADIUM_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// JSON config with timeouts, retries and per-agent endpoints
+    #[arg(long, env = "ARCADIUM_RELAY_CONFIG", default_value = "relay.json")]
+    config: String,
+
+    /// SQLite file for dispatch results
+    #[arg(long, env = "ARCADIUM_RELAY_DB", default_value = "relay.db")]
+    db: String,
+
+    /// Seconds between polls for new jobs
+    #[arg(long, default_value_t = 5)]
+    poll_secs: u64,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let relay = Relay::open(Config::load(&args.config)?, &args.db)?;
+    let source = RpcSource::new(args.url);
+
+    loop {
+        match relay.poll(&source) {
+            Ok(result) => {
+                for d in result.dispatched {
+                    match &d.error {
+                        None => println!("job {} -> {}: delivered", d.job, d.endpoint),
+                        Some(error) => println!("job {} -> {}: failed after {} attempt(s): {error}", d.job, d.endpoint, d.attempts),
+                    }
+                }
+                for s in result.skipped {
+                    eprintln!("skipping job {} for agent {}: {}", s.job, s.agent, s.reason);
+                }
+            }
+            // RPC hiccups are retried on the next poll
+            Err(e) => eprintln!("poll failed: {e:#}"),
+        }
+        std::thread::sleep(Duration::from_secs(args.poll_secs));
+    }
+}
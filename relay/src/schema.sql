@@ -0,0 +1,17 @@
+-- One row per job the relay has finished with, successfully or not. Jobs
+-- without a row are dispatched on the next poll.
+CREATE TABLE IF NOT EXISTS dispatches (
+    job                 TEXT PRIMARY KEY,
+    agent               TEXT NOT NULL,
+    job_id              INTEGER NOT NULL,
+    endpoint            TEXT NOT NULL,
+    status              TEXT NOT NULL,      -- 'delivered' or 'failed'
+    attempts            INTEGER NOT NULL,
+    http_status         INTEGER,
+    response            BLOB,
+    content_hash        TEXT,               -- hex SHA-256 of `response`
+    deliverable_uri     TEXT,
+    error               TEXT,
+    dispatched_at       INTEGER NOT NULL,   -- Unix seconds
+    submitted           INTEGER NOT NULL DEFAULT 0
+);
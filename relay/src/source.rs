@@ -0,0 +1,66 @@
+//! Where the relay learns about new jobs.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use anyhow::Result;
+use arcadium_client::{AgentMetadata, Job, JobStatus};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::commitment_config::CommitmentConfig;
+
+pub trait JobSource {
+    /// Jobs waiting on their agent (`JobStatus::Created`).
+    fn open_jobs(&self) -> Result<Vec<(Pubkey, Job)>>;
+
+    /// The agent's on-chain metadata, if it has any.
+    fn agent_metadata(&self, agent: &Pubkey) -> Result<Option<AgentMetadata>>;
+}
+
+/// [`JobSource`] that scans `Job` accounts over JSON-RPC.
+pub struct RpcSource {
+    client: RpcClient,
+}
+
+impl RpcSource {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+        }
+    }
+}
+
+impl JobSource for RpcSource {
+    fn open_jobs(&self) -> Result<Vec<(Pubkey, Job)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                Job::DISCRIMINATOR.to_vec(),
+            ))]),
+            account_config: RpcAccountInfoConfig::default(),
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = self
+            .client
+            .get_program_accounts_with_config(&arcadium_client::ID, config)?;
+        let mut jobs = Vec::new();
+        for (address, account) in accounts {
+            let job = arcadium_client::decode_job(&account.data)?;
+            if matches!(job.status, JobStatus::Created) {
+                jobs.push((address, job));
+            }
+        }
+        Ok(jobs)
+    }
+
+    fn agent_metadata(&self, agent: &Pubkey) -> Result<Option<AgentMetadata>> {
+        let address = arcadium_client::pda::agent_metadata(agent).0;
+        let account = self
+            .client
+            .get_account_with_commitment(&address, self.client.commitment())?
+            .value;
+        Ok(account
+            .map(|account| arcadium_client::decode_agent_metadata(&account.data))
+            .transpose()?)
+    }
+}
@@ -0,0 +1,318 @@
+//! Relay tests with a local HTTP stub standing in for agent webhooks.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use arcadium_client::{AgentMetadata, Job, JobStatus};
+use arcadium_relay::{is_public, Config, JobRequest, JobSource, Relay, MAX_BODY};
+use sha2::{Digest, Sha256};
+
+/// Serves one scripted `(status, body, delay)` reply per connection, in
+/// order, and keeps the request bodies it saw.
+struct Stub {
+    url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+fn stub(replies: Vec<(u16, &'static str, Duration)>) -> Stub {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+    std::thread::spawn(move || {
+        for (status, body, delay) in replies {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut request = vec![0; content_length];
+            reader.read_exact(&mut request).unwrap();
+            seen.lock().unwrap().push(String::from_utf8(request).unwrap());
+
+            std::thread::sleep(delay);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {status} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+        }
+    });
+    Stub { url, requests }
+}
+
+#[derive(Default)]
+struct Jobs(Vec<(Pubkey, Job)>, Vec<AgentMetadata>);
+
+impl JobSource for Jobs {
+    fn open_jobs(&self) -> Result<Vec<(Pubkey, Job)>> {
+        Ok(self.0.clone())
+    }
+
+    fn agent_metadata(&self, agent: &Pubkey) -> Result<Option<AgentMetadata>> {
+        Ok(self.1.iter().find(|metadata| metadata.agent == *agent).cloned())
+    }
+}
+
+fn metadata(agent: Pubkey, endpoint_url: &str) -> AgentMetadata {
+    AgentMetadata {
+        agent,
+        endpoint_url: endpoint_url.to_string(),
+        description: String::new(),
+        tags: Vec::new(),
+        categories: Vec::new(),
+        encryption_key: None,
+        bump: 255,
+    }
+}
+
+fn open_job(agent: Pubkey, job_id: u64) -> (Pubkey, Job) {
+    let job = Job {
+        agent,
+        job_id,
+        client: Pubkey::new_unique(),
+        task_description: format!("Task {job_id}"),
+        price_lamports: 10_000_000,
+        mint: None,
+        platform_fee_bps: 1000,
+        escrowed_amount: 10_000_000,
+        status: JobStatus::Created,
+        created_at: 1_700_000_000,
+        deadline: None,
+        deliverable_hash: None,
+        deliverable_uri: String::new(),
+        delivered_at: None,
+        review_ends_at: None,
+        completed_at: None,
+        rated: false,
+        bump: 255,
+        escrow_bump: 255,
+    };
+    (arcadium_client::pda::job(&agent, job_id).0, job)
+}
+
+fn config(agent: &Pubkey, url: &str) -> Config {
+    Config {
+        timeout_secs: 1,
+        max_attempts: 3,
+        retry_backoff_ms: 1,
+        retry_failed_secs: 300,
+        endpoints: [(agent.to_string(), url.to_string())].into(),
+    }
+}
+
+#[test]
+fn relay_delivers_once_and_records_result() -> Result<()> {
+    let body = r#"{"deliverable_uri":"ipfs://result"}"#;
+    let server = stub(vec![(200, body, Duration::ZERO)]);
+    let agent = Pubkey::new_unique();
+    let (address, job) = open_job(agent, 0);
+    let jobs = Jobs(vec![(address, job.clone())], Vec::new());
+    let relay = Relay::open_in_memory(config(&agent, &server.url))?;
+
+    let dispatched = relay.poll(&jobs)?.dispatched;
+    assert_eq!(dispatched.len(), 1);
+    let sent: JobRequest = serde_json::from_str(&server.requests.lock().unwrap()[0])?;
+    assert_eq!(sent, JobRequest::new(&address, &job));
+
+    let record = relay.dispatch_record(&address)?.unwrap();
+    assert!(record.delivered);
+    assert_eq!(record.attempts, 1);
+    assert_eq!(record.http_status, Some(200));
+    assert_eq!(record.content_hash, Some(Sha256::digest(body.as_bytes()).into()));
+    assert_eq!(record.deliverable_uri.as_deref(), Some("ipfs://result"));
+    assert_eq!(record, dispatched[0]);
+
+    // Already delivered: not sent again.
+    assert!(relay.poll(&jobs)?.dispatched.is_empty());
+    assert_eq!(server.requests.lock().unwrap().len(), 1);
+
+    assert_eq!(relay.pending_submissions()?.len(), 1);
+    relay.mark_submitted(&address)?;
+    assert!(relay.pending_submissions()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn relay_retries_server_errors_but_not_client_errors() -> Result<()> {
+    let agent = Pubkey::new_unique();
+    let retried = stub(vec![
+        (500, "", Duration::ZERO),
+        (503, "", Duration::ZERO),
+        (200, "done", Duration::ZERO),
+    ]);
+    let relay = Relay::open_in_memory(config(&agent, &retried.url))?;
+    let (address, job) = open_job(agent, 0);
+    let dispatch = relay.dispatch(&address, &job, &retried.url);
+    assert!(dispatch.delivered);
+    assert_eq!(dispatch.attempts, 3);
+    assert_eq!(dispatch.response.as_deref(), Some(&b"done"[..]));
+
+    let rejected = stub(vec![(400, "bad task", Duration::ZERO)]);
+    let dispatch = relay.dispatch(&address, &job, &rejected.url);
+    assert!(!dispatch.delivered);
+    assert_eq!(dispatch.attempts, 1);
+    assert_eq!(dispatch.http_status, Some(400));
+    assert!(dispatch.error.unwrap().contains("bad task"));
+    Ok(())
+}
+
+#[test]
+fn relay_rejects_oversized_responses() -> Result<()> {
+    let agent = Pubkey::new_unique();
+    let body: &'static str = Box::leak("x".repeat(MAX_BODY as usize + 1).into_boxed_str());
+    let server = stub(vec![(200, body, Duration::ZERO)]);
+    let relay = Relay::open_in_memory(config(&agent, &server.url))?;
+    let (address, job) = open_job(agent, 0);
+
+    let dispatch = relay.poll(&Jobs(vec![(address, job)], Vec::new()))?.dispatched.remove(0);
+    assert!(!dispatch.delivered);
+    assert_eq!(dispatch.attempts, 1);
+    assert!(dispatch.response.is_none());
+    assert!(dispatch.error.unwrap().contains("oversized response"));
+    assert!(!relay.dispatch_record(&address)?.unwrap().delivered);
+    Ok(())
+}
+
+#[test]
+fn relay_times_out_and_skips_unknown_agents() -> Result<()> {
+    let agent = Pubkey::new_unique();
+    let slow = stub(vec![
+        (200, "late", Duration::from_secs(3)),
+        (200, "late", Duration::from_secs(3)),
+    ]);
+    let relay = Relay::open_in_memory(Config {
+        max_attempts: 2,
+        ..config(&agent, &slow.url)
+    })?;
+
+    let (address, job) = open_job(agent, 0);
+    let unconfigured = open_job(Pubkey::new_unique(), 0);
+    let result = relay.poll(&Jobs(vec![(address, job), unconfigured.clone()], Vec::new()))?;
+    assert_eq!(result.dispatched.len(), 1);
+    assert!(!result.dispatched[0].delivered);
+    assert_eq!(result.dispatched[0].attempts, 2);
+    assert!(result.dispatched[0].http_status.is_none());
+
+    // No endpoint yet: left for a later poll rather than recorded as failed.
+    assert_eq!(result.skipped.len(), 1);
+    assert_eq!(result.skipped[0].job, unconfigured.0);
+    assert!(relay.dispatch_record(&unconfigured.0)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn relay_falls_back_to_public_on_chain_endpoint() -> Result<()> {
+    let server = stub(vec![(200, "done", Duration::ZERO)]);
+    let configured = Pubkey::new_unique();
+    let loopback = Pubkey::new_unique();
+    let plain_http = Pubkey::new_unique();
+    let unpublished = Pubkey::new_unique();
+    let relay = Relay::open_in_memory(config(&configured, &server.url))?;
+
+    let loopback_url = server.url.replace("http://", "https://");
+    let jobs = Jobs(
+        vec![open_job(configured, 0), open_job(loopback, 0), open_job(plain_http, 0), open_job(unpublished, 0)],
+        vec![metadata(loopback, &loopback_url), metadata(plain_http, &server.url), metadata(unpublished, "")],
+    );
+    let result = relay.poll(&jobs)?;
+
+    // A configured endpoint may be local, a published one may not
+    assert_eq!(result.dispatched.len(), 2);
+    assert_eq!(result.dispatched[0].agent, configured);
+    assert!(result.dispatched[0].delivered);
+    assert_eq!(result.dispatched[1].agent, loopback);
+    assert_eq!(result.dispatched[1].endpoint, loopback_url);
+    assert!(!result.dispatched[1].delivered);
+    assert!(result.dispatched[1].error.as_ref().unwrap().contains("not a public address"));
+    assert_eq!(server.requests.lock().unwrap().len(), 1);
+
+    // Plain http and missing endpoints are never tried
+    let skipped: Vec<Pubkey> = result.skipped.iter().map(|s| s.agent).collect();
+    assert_eq!(skipped, vec![plain_http, unpublished]);
+    Ok(())
+}
+
+#[test]
+fn relay_only_treats_routable_addresses_as_public() {
+    let cases = [
+        // IPv4
+        ("93.184.216.34", true),
+        ("0.0.0.0", false),
+        ("0.1.2.3", false),
+        ("10.0.0.1", false),
+        ("100.64.0.1", false),
+        ("127.0.0.1", false),
+        ("169.254.169.254", false),
+        ("172.16.0.1", false),
+        ("192.0.0.8", false),
+        ("192.168.1.1", false),
+        ("198.18.0.1", false),
+        ("198.19.255.255", false),
+        ("198.20.0.1", true),
+        ("224.0.0.1", false),
+        ("240.0.0.1", false),
+        ("255.255.255.255", false),
+        // IPv6
+        ("2606:2800:220:1::1", true),
+        ("::", false),
+        ("::1", false),
+        ("fc00::1", false),
+        ("fe80::1", false),
+        ("ff02::1", false),
+        // IPv6 carrying an IPv4 address is judged by that address
+        ("::ffff:127.0.0.1", false),
+        ("::ffff:93.184.216.34", true),
+        ("::10.0.0.1", false),
+        ("64:ff9b::7f00:1", false),
+        ("64:ff9b::5db8:d822", true),
+        ("2002:7f00:1::", false),
+        ("2002:a9fe:a9fe::1", false),
+        ("2002:5db8:d822::1", true),
+    ];
+    for (ip, public) in cases {
+        assert_eq!(is_public(ip.parse::<IpAddr>().unwrap()), public, "{ip}");
+    }
+}
+
+#[test]
+fn relay_retries_failed_jobs_after_delay() -> Result<()> {
+    let server = stub(vec![
+        (400, "busy", Duration::ZERO),
+        (400, "busy", Duration::ZERO),
+        (200, "done", Duration::ZERO),
+    ]);
+    let agent = Pubkey::new_unique();
+    let (address, job) = open_job(agent, 0);
+    let jobs = Jobs(vec![(address, job)], Vec::new());
+
+    // Not retried before `retry_failed_secs` have passed...
+    let relay = Relay::open_in_memory(config(&agent, &server.url))?;
+    assert!(!relay.poll(&jobs)?.dispatched[0].delivered);
+    assert!(relay.poll(&jobs)?.dispatched.is_empty());
+
+    // ...and retried on every later poll until delivered.
+    let relay = Relay::open_in_memory(Config {
+        retry_failed_secs: 0,
+        ..config(&agent, &server.url)
+    })?;
+    assert!(!relay.poll(&jobs)?.dispatched[0].delivered);
+    assert!(relay.poll(&jobs)?.dispatched[0].delivered);
+    assert!(relay.poll(&jobs)?.dispatched.is_empty());
+    assert_eq!(server.requests.lock().unwrap().len(), 3);
+    Ok(())
+}